@@ -0,0 +1,139 @@
+use memchr::{memchr, memchr2, memchr3};
+
+/// CharSet is a set of literal separator characters, any one of which
+/// separates two fields
+///
+/// Sets of up to three ASCII characters are searched for with `memchr`, and
+/// larger sets of ASCII characters with a byte lookup table. Since ASCII bytes
+/// never appear within the encoding of a multi-byte UTF-8 character, matching
+/// bytes always fall on char boundaries. Sets including non-ASCII characters
+/// fall back to char comparisons.
+#[derive(Debug)]
+pub enum CharSet {
+    One(u8),
+    Two(u8, u8),
+    Three(u8, u8, u8),
+    Bytes(Box<[bool; 256]>),
+    Chars(Vec<char>),
+}
+
+impl CharSet {
+    pub fn new(chars: &[char]) -> Self {
+        let mut chars = chars.to_vec();
+        chars.sort_unstable();
+        chars.dedup();
+
+        if !chars.iter().all(char::is_ascii) {
+            return CharSet::Chars(chars);
+        }
+
+        let bytes = chars.iter().map(|c| *c as u8).collect::<Vec<_>>();
+        match bytes.as_slice() {
+            [a] => CharSet::One(*a),
+            [a, b] => CharSet::Two(*a, *b),
+            [a, b, c] => CharSet::Three(*a, *b, *c),
+            _ => {
+                let mut table = Box::new([false; 256]);
+                for b in bytes {
+                    table[b as usize] = true;
+                }
+                CharSet::Bytes(table)
+            }
+        }
+    }
+
+    pub fn split<'a>(&self, line: &'a str) -> CharSetSplit<'_, 'a> {
+        CharSetSplit {
+            set: self,
+            remainder: Some(line),
+        }
+    }
+
+    /// Find the byte index and length of the first separator in s
+    fn find(&self, s: &str) -> Option<(usize, usize)> {
+        let bytes = s.as_bytes();
+        match self {
+            CharSet::One(a) => memchr(*a, bytes).map(|i| (i, 1)),
+            CharSet::Two(a, b) => memchr2(*a, *b, bytes).map(|i| (i, 1)),
+            CharSet::Three(a, b, c) => memchr3(*a, *b, *c, bytes).map(|i| (i, 1)),
+            CharSet::Bytes(table) => bytes.iter().position(|b| table[*b as usize]).map(|i| (i, 1)),
+            CharSet::Chars(chars) => s
+                .char_indices()
+                .find(|(_, c)| chars.contains(c))
+                .map(|(i, c)| (i, c.len_utf8())),
+        }
+    }
+}
+
+pub struct CharSetSplit<'s, 'a> {
+    set: &'s CharSet,
+    remainder: Option<&'a str>,
+}
+
+impl<'a> Iterator for CharSetSplit<'_, 'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remainder = self.remainder?;
+        match self.set.find(remainder) {
+            Some((i, len)) => {
+                self.remainder = Some(&remainder[i + len..]);
+                Some(&remainder[..i])
+            }
+            None => {
+                self.remainder = None;
+                Some(remainder)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn split<'a>(chars: &[char], line: &'a str) -> Vec<&'a str> {
+        CharSet::new(chars).split(line).collect()
+    }
+
+    #[test]
+    fn split_one() {
+        assert_eq!(vec!["a", "b", ""], split(&[','], "a,b,"));
+    }
+
+    #[test]
+    fn split_two() {
+        assert_eq!(vec!["a", "b", "c"], split(&[',', ';'], "a,b;c"));
+    }
+
+    #[test]
+    fn split_three() {
+        assert_eq!(vec!["a", "b", "", "c"], split(&[',', ';', '|'], "a,b|;c"));
+    }
+
+    #[test]
+    fn split_byte_table() {
+        assert_eq!(vec!["a", "b", "c", "d", "e"], split(&[',', ';', '|', ':'], "a,b;c|d:e"));
+    }
+
+    #[test]
+    fn split_non_ascii() {
+        assert_eq!(vec!["a", "b", "c"], split(&[',', '¦'], "a¦b,c"));
+    }
+
+    #[test]
+    fn split_multibyte_fields() {
+        assert_eq!(vec!["é", "ü", "ñ"], split(&[',', ';'], "é,ü;ñ"));
+    }
+
+    #[test]
+    fn split_no_separator() {
+        assert_eq!(vec!["abc"], split(&[',', ';'], "abc"));
+        assert_eq!(vec![""], split(&[',', ';'], ""));
+    }
+
+    #[test]
+    fn new_dedups() {
+        assert!(matches!(CharSet::new(&[',', ',', ';']), CharSet::Two(b',', b';')));
+    }
+}
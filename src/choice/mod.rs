@@ -1,3 +1,4 @@
+use regex::Regex;
 use std::cmp::max;
 use std::convert::TryInto;
 
@@ -15,10 +16,47 @@ pub struct Choice {
     pub start: isize,
     pub end: isize,
     pub kind: ChoiceKind,
+    pub step: isize,
+    pub nested: Option<Box<Nested>>,
+    pub excluded: bool,
+    pub anchor: Option<Anchor>,
+    pub subscript: Option<Box<Choice>>,
+    /// The choice as written on the command line, for messages
+    pub source: String,
     negative_index: bool,
     reversed: bool,
 }
 
+/// Nested is the part of a hierarchical choice (e.g. `3/:/1`) that is applied
+/// to each field chosen by its parent, splitting that field with its own
+/// separator
+#[derive(Debug)]
+pub struct Nested {
+    pub separator: Separator,
+    pub choice: Choice,
+}
+
+/// Anchor makes the indexes of a choice (e.g. `@/--host/+1`) relative to the
+/// first (or last) field of a line that matches a regex, rather than to the
+/// start and end of the line
+#[derive(Debug)]
+pub struct Anchor {
+    pub regex: Regex,
+    pub last: bool,
+}
+
+impl Anchor {
+    /// Find the index of the anchor field, if any field matches
+    fn find<S: AsRef<str>>(&self, fields: &[S]) -> Option<usize> {
+        let is_match = |field: &S| self.regex.is_match(field.as_ref());
+        if self.last {
+            fields.iter().rposition(is_match)
+        } else {
+            fields.iter().position(is_match)
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ChoiceKind {
     Single,
@@ -35,6 +73,12 @@ impl Choice {
             start,
             end,
             kind,
+            step: 1,
+            nested: None,
+            excluded: false,
+            anchor: None,
+            subscript: None,
+            source: String::new(),
             negative_index,
             reversed,
         }
@@ -46,29 +90,67 @@ impl Choice {
         config: &Config,
         handle: &mut Writer<WR>,
     ) -> Result<()> {
+        if self.nested.is_some()
+            || self.subscript.is_some()
+            || self.anchor.is_some()
+            || self.step != 1
+            || config.opt.fill.is_some()
+            || ((config.opt.escape.is_some() || config.opt.quote.is_some()) && !config.opt.character_wise)
+        {
+            let fields = config.fields(line);
+            return self.print_choice_fields(&fields, config, handle);
+        }
+
         if !config.opt.character_wise {
-            // effectively ignore would-be choices that are empty as long as non-greedy is not enabled
-            let predicate = |s: &&str| config.opt.non_greedy || !s.is_empty();
-            
             match &config.separator {
                 Separator::LiteralChar(c) => {
-                    let i = line.split(*c).filter(predicate);
+                    let i = config.empty_fields.filter(line.split(*c));
+                    self.print_choice_generic(i, config, handle)
+                }
+                Separator::LiteralStr(s) => {
+                    let i = config.empty_fields.filter(line.split(s.as_str()));
                     self.print_choice_generic(i, config, handle)
                 }
-                Separator::Regex(r) => {    
-                    let i = r.split(line).filter(predicate);
+                Separator::LiteralChars(set) => {
+                    let i = config.empty_fields.filter(set.split(line));
+                    self.print_choice_generic(i, config, handle)
+                }
+                Separator::Regex(r) => {
+                    let i = config.empty_fields.filter(r.split(line));
                     self.print_choice_generic(i, config, handle)
                 }
                 Separator::Whitespace => {
                     let i = line.split_whitespace();
                     self.print_choice_generic(i, config, handle)
                 }
+                Separator::AsciiWhitespace => {
+                    let i = line.split_ascii_whitespace();
+                    self.print_choice_generic(i, config, handle)
+                }
+                Separator::WhitespaceChars(chars) => {
+                    let i = line.split(chars.as_slice()).filter(|s| !s.is_empty());
+                    self.print_choice_generic(i, config, handle)
+                }
+                Separator::Pattern(_) => {
+                    let i = config.separator.split(line, config.empty_fields).into_iter();
+                    self.print_choice_generic(i, config, handle)
+                }
             }
         } else {
             self.print_choice_generic(line.chars(), config, handle)
         } 
     }
 
+    /// Make the indexes of this choice relative to an anchor field
+    ///
+    /// Relative indexes are offsets from the anchor, so a negative index is
+    /// before the anchor rather than from the end of the line.
+    pub fn set_anchor(&mut self, anchor: Anchor) {
+        self.negative_index = false;
+        self.reversed = self.end < self.start;
+        self.anchor = Some(anchor);
+    }
+
     pub fn is_reverse_range(&self) -> bool {
         self.reversed
     }
@@ -77,6 +159,101 @@ impl Choice {
         self.negative_index
     }
 
+    /// Print a choice from fields that have already been split from a line
+    ///
+    /// If the choice is nested, each chosen field is split again with the
+    /// nested separator and the nested choice is printed from those fields.
+    /// With `--fill`, the fill text is printed in place of each missing field.
+    pub fn print_choice_fields<WR: WriteReceiver, S: AsRef<str>>(
+        &self,
+        fields: &[S],
+        config: &Config,
+        handle: &mut Writer<WR>,
+    ) -> Result<()> {
+        let fill = match self.fill(config) {
+            Some(fill) => fill,
+            None => {
+                let indices = self.resolve(fields)?;
+                return self.print_fields_at(indices, fields, config, handle);
+            }
+        };
+
+        for position in self.filled_indices(fields.len())? {
+            match position {
+                Some(i) => self.print_fields_at(Some(i), fields, config, handle)?,
+                None => handle.write_choice_separable(fill, config)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// The text printed in place of each of this choice's fields that is
+    /// missing from a line, if `--fill` is given and the choice is not anchored
+    pub fn fill<'a>(&self, config: &'a Config) -> Option<&'a str> {
+        config.opt.fill.as_deref().filter(|_| self.anchor.is_none())
+    }
+
+    /// Print the fields at the given indexes as if chosen by this choice,
+    /// including applying any nested choice or subscript to each of them
+    pub fn print_fields_at<WR, S, I>(
+        &self,
+        indices: I,
+        fields: &[S],
+        config: &Config,
+        handle: &mut Writer<WR>,
+    ) -> Result<()>
+    where
+        WR: WriteReceiver,
+        S: AsRef<str>,
+        I: IntoIterator<Item = usize>,
+    {
+        for i in indices {
+            match &self.nested {
+                None => match &self.subscript {
+                    None => handle.write_choice_separable(fields[i].as_ref(), config)?,
+                    Some(subscript) => {
+                        // a subscript beyond the field's characters chooses nothing,
+                        // which produces_field agrees is not a field
+                        let field = subscript.substring(fields[i].as_ref())?;
+                        if !field.is_empty() {
+                            handle.write_choice_separable(field.as_str(), config)?
+                        }
+                    }
+                },
+                Some(nested) => {
+                    let subfields = nested
+                        .separator
+                        .split(fields[i].as_ref(), config.empty_fields);
+                    nested.choice.print_choice_fields(&subfields, config, handle)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether this choice produces any field from the fields of a line,
+    /// including through any nested choice or subscript
+    pub fn produces_field<S: AsRef<str>>(&self, fields: &[S], config: &Config) -> Result<bool> {
+        for i in self.resolve(fields)? {
+            let produced = match &self.nested {
+                None => match &self.subscript {
+                    None => true,
+                    Some(subscript) => !subscript.substring(fields[i].as_ref())?.is_empty(),
+                },
+                Some(nested) => {
+                    let subfields = nested
+                        .separator
+                        .split(fields[i].as_ref(), config.empty_fields);
+                    nested.choice.produces_field(&subfields, config)?
+                }
+            };
+            if produced {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     fn print_choice_generic<WR, T, I>(
         &self,
         mut iter: I,
@@ -134,23 +311,8 @@ impl Choice {
     {
         let vec = iter.collect::<Vec<_>>();
 
-        if let Some((start, end)) = self.get_negative_start_end(&vec)? {
-            if end > start {
-                for word in vec[start..std::cmp::min(end, vec.len() - 1)].iter() {
-                    handle.write_choice(*word, config, true)?;
-                }
-                handle.write_choice(vec[std::cmp::min(end, vec.len() - 1)], config, false)?;
-            } else if self.start < 0 {
-                for word in vec[end + 1..=std::cmp::min(start, vec.len() - 1)]
-                    .iter()
-                    .rev()
-                {
-                    handle.write_choice(*word, config, true)?;
-                }
-                handle.write_choice(vec[end], config, false)?;
-            } else if start == end && self.start < vec.len().try_into()? {
-                handle.write_choice(vec[start], config, false)?;
-            }
+        for i in self.indices(vec.len())? {
+            handle.write_choice_separable(vec[i], config)?;
         }
 
         Ok(())
@@ -183,44 +345,264 @@ impl Choice {
             }
         }
 
-        let mut peek_iter = stack.iter().rev().peekable();
-        while let Some(s) = peek_iter.next() {
-            handle.write_choice(*s, config, peek_iter.peek().is_some())?;
+        for s in stack.iter().rev() {
+            handle.write_choice_separable(*s, config)?;
         }
 
         Ok(())
     }
 
-    /// Get the absolute indexes of a choice range based on the slice length
+    /// Choose characters from a field, as the subscript of a choice (e.g. the
+    /// `[0:7]` of `3[0:7]`), joining them without a separator
+    fn substring(&self, field: &str) -> Result<String> {
+        let chars = chars(field);
+        Ok(self.resolve(&chars)?.into_iter().map(|i| chars[i]).collect())
+    }
+
+    /// Get the indexes of the fields this choice selects from the fields of a
+    /// line, in the order they are printed
+    ///
+    /// Unlike indices, this can resolve choices anchored to a field.
+    pub fn resolve<S: AsRef<str>>(&self, fields: &[S]) -> Result<Vec<usize>> {
+        let anchor = match &self.anchor {
+            None => return self.indices(fields.len()),
+            Some(anchor) => anchor.find(fields),
+        };
+
+        match anchor {
+            Some(anchor) => match self.relative_to(anchor)? {
+                Some(choice) => choice.indices(fields.len()),
+                None => Ok(Vec::new()),
+            },
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Convert an anchored choice into an equivalent unanchored choice, given
+    /// the index of the anchor field
+    ///
+    /// Returns Ok(None) if the choice would only include fields before the
+    /// start of the line.
+    fn relative_to(&self, anchor: usize) -> Result<Option<Choice>> {
+        let anchor: isize = anchor.try_into()?;
+        let start = anchor.saturating_add(self.start);
+        let end = anchor.saturating_add(self.end);
+
+        if start < 0 && (self.kind == ChoiceKind::Single || end < 0) {
+            return Ok(None);
+        }
+
+        let mut choice = Choice::new(max(start, 0), max(end, 0), ChoiceKind::ColonRange);
+        choice.reversed = self.reversed;
+        choice.step = self.step;
+        Ok(Some(choice))
+    }
+
+    /// Get the indexes of the items this choice selects from a line with `len`
+    /// items, in the order they are printed
+    ///
+    /// A step other than 1 takes every step-th item of the range, walking it
+    /// from its end rather than its start if the step is negative.
+    pub fn indices(&self, len: usize) -> Result<Vec<usize>> {
+        let mut indices = self.range_indices(len)?;
+
+        if self.step != 1 {
+            if self.step < 0 {
+                indices.reverse();
+            }
+            indices = indices
+                .into_iter()
+                .step_by(self.step.unsigned_abs())
+                .collect();
+        }
+
+        Ok(indices)
+    }
+
+    /// Whether this choice selects every position it covers from the fields of
+    /// a line, i.e. none of them are missing and it is not an empty range,
+    /// including through any nested choice or subscript of each chosen field
+    pub fn is_satisfied_by<S: AsRef<str>>(&self, fields: &[S], config: &Config) -> Result<bool> {
+        if !self.covers(fields)? {
+            return Ok(false);
+        }
+        if self.nested.is_none() && self.subscript.is_none() {
+            return Ok(true);
+        }
+
+        for i in self.resolve(fields)? {
+            let satisfied = match (&self.nested, &self.subscript) {
+                (Some(nested), _) => {
+                    let subfields = nested
+                        .separator
+                        .split(fields[i].as_ref(), config.empty_fields);
+                    nested.choice.is_satisfied_by(&subfields, config)?
+                }
+                (None, Some(subscript)) => subscript.is_satisfied_by(&chars(fields[i].as_ref()), config)?,
+                (None, None) => true,
+            };
+            if !satisfied {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Whether this choice selects every position it covers from the fields of
+    /// a line, ignoring any nested choice or subscript
+    fn covers<S: AsRef<str>>(&self, fields: &[S]) -> Result<bool> {
+        if self.anchor.is_some() {
+            return Ok(!self.resolve(fields)?.is_empty());
+        }
+
+        let (low, high, descending) = match self.bounds(fields.len())? {
+            Some(bounds) => bounds,
+            None => return Ok(!self.indices(fields.len())?.is_empty()),
+        };
+        if low > high {
+            return Ok(false);
+        }
+
+        // only the positions a step lands on need to be in the line
+        let span = (high - low) / self.step.abs() * self.step.abs();
+        let (first, last) = match descending {
+            true => (high - span, high),
+            false => (low, low + span),
+        };
+        Ok(first >= 0 && last < fields.len().try_into()?)
+    }
+
+    /// Get the positions this choice selects from a line with `len` items
+    /// like indices, but with None for each position that is missing from the
+    /// line
+    ///
+    /// A single index or a range with an end is always the same number of
+    /// positions, whatever the length of the line, so the positions are
+    /// generated as they are needed rather than all at once. A range without
+    /// an end, or with one end counted from the start of the line and the
+    /// other from its end, only covers the items in the line.
+    fn filled_indices(&self, len: usize) -> Result<Box<dyn Iterator<Item = Option<usize>>>> {
+        Ok(Box::new(self.filled_positions(len)?.map(|(_, i)| i)))
+    }
+
+    /// Get the positions this choice selects from a line with `len` items
+    /// like filled_indices, each along with its index if it is in the line
+    ///
+    /// Positions before the line are negative and those after it are at least
+    /// `len`, so that missing fields can be told apart and ordered.
+    pub fn filled_positions(&self, len: usize) -> Result<Box<dyn Iterator<Item = (isize, Option<usize>)>>> {
+        let (low, high, descending) = match self.bounds(len)? {
+            Some(bounds) => bounds,
+            None => {
+                let indices = self.indices(len)?.into_iter();
+                return Ok(Box::new(indices.map(|i| (i as isize, Some(i)))));
+            }
+        };
+
+        let step = self.step.unsigned_abs();
+        let positions: Box<dyn Iterator<Item = isize>> = match descending {
+            true => Box::new((low..=high).rev().step_by(step)),
+            false => Box::new((low..=high).step_by(step)),
+        };
+
+        let len: isize = len.try_into()?;
+        Ok(Box::new(positions.map(move |i| {
+            if (0..len).contains(&i) {
+                (i, Some(i as usize))
+            } else {
+                (i, None)
+            }
+        })))
+    }
+
+    /// Get the lowest and highest positions a single index or a range with an
+    /// end covers in a line with `len` items, which may be beyond the line,
+    /// and whether they are chosen from the highest down
+    ///
+    /// The range is empty if the lowest position is above the highest. None is
+    /// returned for a choice that only covers the items in the line (see
+    /// filled_indices).
+    fn bounds(&self, len: usize) -> Result<Option<(isize, isize, bool)>> {
+        if self.kind != ChoiceKind::Single
+            && (self.end == isize::MAX || (self.has_negative_index() && (self.start < 0) != (self.end < 0)))
+        {
+            return Ok(None);
+        }
+
+        let len: isize = len.try_into()?;
+        let absolute = |i: isize| match self.has_negative_index() && i < 0 {
+            true => len.saturating_add(i),
+            false => i,
+        };
+        let (start, end) = (absolute(self.start), absolute(self.end));
+
+        Ok(Some(match self.is_reverse_range() {
+            true => (end, start, self.step > 0),
+            false => (start, end, self.step < 0),
+        }))
+    }
+
+    fn range_indices(&self, len: usize) -> Result<Vec<usize>> {
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+
+        if !(self.is_reverse_range() || self.has_negative_index()) {
+            let start: usize = self.start.try_into()?;
+            if self.end < self.start || start >= len {
+                return Ok(Vec::new());
+            }
+            let end: usize = self.end.try_into()?;
+            Ok((start..=std::cmp::min(end, len - 1)).collect())
+        } else if self.is_reverse_range() && !self.has_negative_index() {
+            let end: usize = self.end.try_into()?;
+            if end >= len {
+                return Ok(Vec::new());
+            }
+            let start: usize = self.start.try_into()?;
+            Ok((end..=std::cmp::min(start, len - 1)).rev().collect())
+        } else {
+            match self.negative_start_end(len)? {
+                Some((start, end)) if end > start => Ok((start..=end).collect()),
+                Some((start, end)) if self.start < 0 => Ok((end..=start).rev().collect()),
+                Some((start, end)) if start == end && self.start < len.try_into()? => {
+                    Ok(vec![start])
+                }
+                _ => Ok(Vec::new()),
+            }
+        }
+    }
+
+    /// Get the absolute indexes of a choice range based on the number of items in a line
     ///
     /// N.B. that this assumes that at least one index is negative - do not try to call this
     /// function with a purely positive range.
     ///
-    /// Returns Ok(None) if the resulting choice range would not include any item in the slice.
-    fn get_negative_start_end<T>(&self, slice: &[T]) -> Result<Option<(usize, usize)>> {
-        if slice.is_empty() {
+    /// Returns Ok(None) if the resulting choice range would not include any item in the line.
+    fn negative_start_end(&self, len: usize) -> Result<Option<(usize, usize)>> {
+        if len == 0 {
             return Ok(None);
         }
 
         let start_abs = self.start.checked_abs().ok_or_else(|| {
-            Error::Config(format!(
+            Error::Index(format!(
                 "Minimum index value supported is isize::MIN + 1 ({})",
                 isize::MIN + 1
             ))
         })?;
 
-        let slice_len_as_isize = slice.len().try_into()?;
+        let len_as_isize = len.try_into()?;
 
         if self.kind == ChoiceKind::Single {
-            if start_abs <= slice_len_as_isize {
-                let idx = (slice_len_as_isize - start_abs).try_into()?;
+            if start_abs <= len_as_isize {
+                let idx = (len_as_isize - start_abs).try_into()?;
                 Ok(Some((idx, idx)))
             } else {
                 Ok(None)
             }
         } else {
             let end_abs = self.end.checked_abs().ok_or_else(|| {
-                Error::Config(format!(
+                Error::Index(format!(
                     "Minimum index value supported is isize::MIN + 1 ({})",
                     isize::MIN + 1
                 ))
@@ -228,39 +610,29 @@ impl Choice {
 
             if self.start >= 0 {
                 // then we assume self.end is negative
-                let start = self.start.try_into()?;
-
-                if end_abs <= slice_len_as_isize || start <= slice.len() || start > slice.len() {
-                    let end = slice.len().saturating_sub(end_abs.try_into()?);
-                    Ok(Some((
-                        std::cmp::min(start, slice.len().saturating_sub(1)),
-                        std::cmp::min(end, slice.len().saturating_sub(1)),
-                    )))
-                } else {
-                    Ok(None)
-                }
+                let start: usize = self.start.try_into()?;
+                let end = len.saturating_sub(end_abs.try_into()?);
+                Ok(Some((
+                    std::cmp::min(start, len.saturating_sub(1)),
+                    std::cmp::min(end, len.saturating_sub(1)),
+                )))
             } else if self.end >= 0 {
                 // then we assume self.start is negative
-                let end = self.end.try_into()?;
-
-                if start_abs <= slice_len_as_isize || end <= slice.len() || end > slice.len() {
-                    let start = slice.len().saturating_sub(start_abs.try_into()?);
-                    Ok(Some((
-                        std::cmp::min(start, slice.len().saturating_sub(1)),
-                        std::cmp::min(end, slice.len().saturating_sub(1)),
-                    )))
-                } else {
-                    Ok(None)
-                }
+                let end: usize = self.end.try_into()?;
+                let start = len.saturating_sub(start_abs.try_into()?);
+                Ok(Some((
+                    std::cmp::min(start, len.saturating_sub(1)),
+                    std::cmp::min(end, len.saturating_sub(1)),
+                )))
             } else {
                 // both indices are negative
-                let start = slice.len().saturating_sub(start_abs.try_into()?);
-                let end = slice.len().saturating_sub(end_abs.try_into()?);
+                let start = len.saturating_sub(start_abs.try_into()?);
+                let end = len.saturating_sub(end_abs.try_into()?);
 
-                if start_abs <= slice_len_as_isize || end_abs <= slice_len_as_isize {
+                if start_abs <= len_as_isize || end_abs <= len_as_isize {
                     Ok(Some((
-                        std::cmp::min(start, slice.len().saturating_sub(1)),
-                        std::cmp::min(end, slice.len().saturating_sub(1)),
+                        std::cmp::min(start, len.saturating_sub(1)),
+                        std::cmp::min(end, len.saturating_sub(1)),
                     )))
                 } else {
                     Ok(None)
@@ -269,3 +641,11 @@ impl Choice {
         }
     }
 }
+
+/// Split a field into its characters, which a subscript chooses from
+fn chars(field: &str) -> Vec<&str> {
+    field
+        .char_indices()
+        .map(|(i, c)| &field[i..i + c.len_utf8()])
+        .collect()
+}
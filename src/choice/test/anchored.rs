@@ -0,0 +1,104 @@
+use super::*;
+
+#[test]
+fn print_field_after_anchor() {
+    test_fn(
+        vec!["choose", "@/--host/+1"],
+        "ssh -v --host example.com -p 22",
+        "example.com",
+    );
+}
+
+#[test]
+fn print_two_fields_after_anchor() {
+    test_fn(
+        vec!["choose", "@/--host/1:2"],
+        "cmd --host example.com 22 --verbose",
+        "example.com 22",
+    );
+}
+
+#[test]
+fn print_anchor_to_end() {
+    test_fn(
+        vec!["choose", "@/^ERR/:"],
+        "2020-01-01 app ERROR disk full",
+        "ERROR disk full",
+    );
+}
+
+#[test]
+fn print_anchor_itself() {
+    test_fn(vec!["choose", "@/^user=/0"], "a b user=alice c", "user=alice");
+}
+
+#[test]
+fn print_field_before_anchor() {
+    test_fn(vec!["choose", "@/=/-1"], "a b c=d e", "b");
+}
+
+#[test]
+fn print_around_anchor() {
+    test_fn(vec!["choose", "@/c/-1:1"], "a b c d e", "b c d");
+}
+
+#[test]
+fn print_reversed_around_anchor() {
+    test_fn(vec!["choose", "@/c/1:-1"], "a b c d e", "d c b");
+}
+
+#[test]
+fn print_before_start_of_line() {
+    test_fn(vec!["choose", "@/b/-3"], "a b c", "");
+    test_fn(vec!["choose", "@/b/-3:0"], "a b c", "a b");
+}
+
+#[test]
+fn print_after_end_of_line() {
+    test_fn(vec!["choose", "@/c/+1"], "a b c", "");
+}
+
+#[test]
+fn print_first_matching_anchor() {
+    test_fn(vec!["choose", "@/^x/1"], "x1 a x2 b", "a");
+}
+
+#[test]
+fn print_last_matching_anchor() {
+    test_fn(vec!["choose", "@@/^x/1"], "x1 a x2 b", "b");
+}
+
+#[test]
+fn print_no_matching_anchor() {
+    test_fn(vec!["choose", "0", "@/^z/1", "1"], "a b c", "a b");
+}
+
+#[test]
+fn print_anchored_exclusive() {
+    test_fn(vec!["choose", "-x", "@/a/0:2"], "a b c d", "a b");
+}
+
+#[test]
+fn print_anchored_one_indexed() {
+    test_fn(vec!["choose", "--one-indexed", "@/a/1"], "x a b c", "b");
+}
+
+#[test]
+fn print_anchored_nested() {
+    test_fn(vec!["choose", "@/^user=/0/=/1"], "GET / user=alice 200", "alice");
+}
+
+#[test]
+fn print_anchored_step() {
+    test_fn(vec!["choose", "@/a/::2"], "x a b c d e", "a c e");
+}
+
+#[test]
+fn print_anchored_field_separator() {
+    test_fn(vec!["choose", "-f", ",", "@/^id$/1"], "x,id,42,y", "42");
+}
+
+#[test]
+fn print_anchored_excluded() {
+    test_fn(vec!["choose", "!@/^-v$/0"], "cmd -v arg", "cmd arg");
+}
@@ -0,0 +1,105 @@
+use crate::error::Error;
+
+use super::*;
+
+#[test]
+fn print_complement_single() {
+    test_fn(vec!["choose", "--complement", "1"], "a b c d", "a c d");
+}
+
+#[test]
+fn print_complement_several() {
+    test_fn(vec!["choose", "--complement", "2", "-1"], "a b c d e", "a b d");
+}
+
+#[test]
+fn print_complement_range() {
+    test_fn(vec!["choose", "--complement", "1:2"], "a b c d e", "a d e");
+}
+
+#[test]
+fn print_complement_negative_range() {
+    test_fn(vec!["choose", "--complement", "-3:-2"], "a b c d e", "a b e");
+}
+
+#[test]
+fn print_complement_keeps_original_order() {
+    test_fn(vec!["choose", "--complement", "3", "0"], "a b c d e", "b c e");
+}
+
+#[test]
+fn print_complement_reversed_range() {
+    test_fn(vec!["choose", "--complement", "3:1"], "a b c d e", "a e");
+}
+
+#[test]
+fn print_complement_out_of_range() {
+    test_fn(vec!["choose", "--complement", "9"], "a b c", "a b c");
+}
+
+#[test]
+fn print_complement_everything() {
+    test_fn(vec!["choose", "--complement", ":"], "a b c", "");
+}
+
+#[test]
+fn print_complement_resolved_per_line() {
+    test_fn(vec!["choose", "--complement", "-1"], "a b", "a");
+    test_fn(vec!["choose", "--complement", "-1"], "a b c d", "a b c");
+}
+
+#[test]
+fn print_complement_exclusive() {
+    test_fn(vec!["choose", "--complement", "-x", "1:3"], "a b c d e", "a d e");
+}
+
+#[test]
+fn print_complement_one_indexed() {
+    test_fn(vec!["choose", "--complement", "--one-indexed", "1"], "a b c", "b c");
+}
+
+#[test]
+fn print_complement_character_wise() {
+    test_fn(vec!["choose", "--complement", "-c", "1:2"], "abcde", "ade");
+}
+
+#[test]
+fn print_complement_field_separator() {
+    test_fn(vec!["choose", "--complement", "-f", ",", "-o", ",", "1"], "a,b,c", "a,c");
+}
+
+#[test]
+fn print_excluded_choice() {
+    test_fn(vec!["choose", "!2", "!-1"], "a b c d e", "a b d");
+}
+
+#[test]
+fn print_excluded_choice_comma_separated() {
+    test_fn(vec!["choose", "!0,!-1"], "a b c d e", "b c d");
+}
+
+#[test]
+fn print_excluded_choice_with_included_choice() {
+    test_fn(vec!["choose", "0:4", "!2"], "a b c d e f", "a b d e");
+}
+
+#[test]
+fn print_excluded_choice_with_included_choices_in_order() {
+    test_fn(vec!["choose", "3:0", "!1", "-1"], "a b c d e", "d c a e");
+}
+
+#[test]
+fn print_excluded_choice_with_nested_choice() {
+    test_fn(vec!["choose", "!1", "0:2/=/1"], "a=1 b=2 c=3", "1 3");
+}
+
+#[test]
+fn complement_nested_choice_is_rejected() {
+    let e = Config::new(Opt::from_iter(vec!["choose", "--complement", "1", "0/:/1"])).err().unwrap();
+    assert!(matches!(e, Error::Parse(..)));
+}
+
+#[test]
+fn print_excluded_choice_fill() {
+    test_fn(vec!["choose", "--fill", "-", "0:3", "!1"], "a b c", "a c -");
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+#[test]
+fn print_comma_separated_choices() {
+    test_fn(vec!["choose", "0,2,4:"], "a b c d e f", "a c e f");
+}
+
+#[test]
+fn print_comma_separated_choices_with_other_args() {
+    test_fn(vec!["choose", "0,2", "-1"], "a b c d e f", "a c f");
+}
+
+#[test]
+fn print_cut_syntax_single() {
+    test_fn(vec!["choose", "--cut-syntax", "1"], "a b c", "a");
+}
+
+#[test]
+fn print_cut_syntax_list() {
+    // cut -f 1,3,5-7
+    test_fn(
+        vec!["choose", "--cut-syntax", "1,3,5-7"],
+        "a b c d e f g h",
+        "a c e f g",
+    );
+}
+
+#[test]
+fn print_cut_syntax_open_start() {
+    test_fn(vec!["choose", "--cut-syntax", "-2"], "a b c d", "a b");
+}
+
+#[test]
+fn print_cut_syntax_open_end() {
+    test_fn(vec!["choose", "--cut-syntax", "3-"], "a b c d", "c d");
+}
+
+#[test]
+fn print_cut_syntax_like_cut() {
+    // cut -d, -f2-3
+    test_fn(
+        vec!["choose", "--cut-syntax", "-f", ",", "-o", ",", "--empty-fields", "keep", "2-3"],
+        "a,,c,d",
+        ",c",
+    );
+}
@@ -0,0 +1,113 @@
+use super::*;
+
+#[test]
+fn print_drop_empty_fields() {
+    test_fn(vec!["choose", "-f", ",", "--empty-fields", "drop", "1"], ",a,,b", "b");
+}
+
+#[test]
+fn print_keep_empty_fields_like_cut() {
+    // cut -d, -f3
+    test_fn(vec!["choose", "-f", ",", "--empty-fields", "keep", "2"], "a,,c", "c");
+}
+
+#[test]
+fn print_keep_empty_fields_range_like_cut() {
+    // cut -d, -f1-3
+    test_fn(
+        vec!["choose", "-f", ",", "-o", ",", "--empty-fields", "keep", "0:2"],
+        "a,,c,d",
+        "a,,c",
+    );
+}
+
+#[test]
+fn print_keep_empty_fields_leading() {
+    test_fn(
+        vec!["choose", "-f", ",", "-o", ",", "--empty-fields", "keep", "0:1"],
+        ",b,c",
+        ",b",
+    );
+}
+
+#[test]
+fn print_keep_empty_fields_separate_choices() {
+    test_fn(
+        vec!["choose", "-f", ",", "-o", ",", "--empty-fields", "keep", "1", "0", "2"],
+        "a,,c",
+        ",a,c",
+    );
+}
+
+#[test]
+fn print_keep_empty_fields_negative() {
+    test_fn(
+        vec!["choose", "-f", ",", "-o", ",", "--empty-fields", "keep", "-3:-1"],
+        "a,b,,d",
+        "b,,d",
+    );
+}
+
+#[test]
+fn print_keep_empty_fields_reversed() {
+    test_fn(
+        vec!["choose", "-f", ",", "-o", ",", "--empty-fields", "keep", "3:1"],
+        "a,b,,d",
+        "d,,b",
+    );
+}
+
+#[test]
+fn print_keep_interior_empty_fields() {
+    test_fn(
+        vec!["choose", "-f", ",", "-o", ",", "--empty-fields", "keep-interior", ":"],
+        ",,a,,b,",
+        "a,,b",
+    );
+}
+
+#[test]
+fn print_keep_interior_empty_fields_indexing() {
+    test_fn(
+        vec!["choose", "-f", ",", "--empty-fields", "keep-interior", "0", "-1"],
+        ",a,,b,",
+        "a b",
+    );
+}
+
+#[test]
+fn print_keep_empty_fields_regex_separator() {
+    test_fn(
+        vec!["choose", "-f", "[,;]", "-o", ",", "--empty-fields", "keep", "1:2"],
+        "a;,b",
+        ",b",
+    );
+}
+
+#[test]
+fn print_keep_empty_fields_escaped() {
+    test_fn(
+        vec!["choose", "-f", ",", "--escape", "\\", "--empty-fields", "keep", "2"],
+        "a\\,,,c",
+        "c",
+    );
+}
+
+#[test]
+fn print_keep_empty_fields_nested() {
+    test_fn(
+        vec!["choose", "--empty-fields", "keep", "1/,/2"],
+        "x a,,c",
+        "c",
+    );
+}
+
+#[test]
+fn print_non_greedy_keeps_empty_fields() {
+    test_fn(vec!["choose", "-f", ",", "-o", ",", "-n", "0:2"], "a,,c", "a,,c");
+}
+
+#[test]
+fn print_negative_after_other_choice() {
+    test_fn(vec!["choose", "0", "-1"], "a b c d", "a d");
+}
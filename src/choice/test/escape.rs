@@ -0,0 +1,60 @@
+use super::*;
+
+#[test]
+fn print_escaped_pipe() {
+    test_fn(
+        vec!["choose", "-f", "\\|", "--escape", "\\", "0"],
+        "a\\|b|c",
+        "a\\|b",
+    );
+}
+
+#[test]
+fn print_escaped_pipe_unescaped() {
+    test_fn(
+        vec!["choose", "-f", "\\|", "--escape", "\\", "--unescape", "0:1"],
+        "a\\|b|c",
+        "a|b c",
+    );
+}
+
+#[test]
+fn print_escaped_literal_string_separator() {
+    test_fn(
+        vec!["choose", "-f", "::", "--escape", "%", "--unescape", "-1"],
+        "a::b%::c",
+        "b::c",
+    );
+}
+
+#[test]
+fn print_escaped_negative_index() {
+    test_fn(
+        vec!["choose", "-f", ",", "--escape", "\\", "-2"],
+        "a,b\\,c,d",
+        "b\\,c",
+    );
+}
+
+#[test]
+fn print_escaped_greedy() {
+    test_fn(
+        vec!["choose", "-f", ",", "--escape", "\\", "1"],
+        "a,,b\\,c",
+        "b\\,c",
+    );
+}
+
+#[test]
+fn print_escaped_non_greedy() {
+    test_fn(
+        vec!["choose", "-f", ",", "--escape", "\\", "-n", "2"],
+        "a,,b\\,c",
+        "b\\,c",
+    );
+}
+
+#[test]
+fn print_literal_string_separator() {
+    test_fn(vec!["choose", "-f", "::", "1:2"], "a::b::c::d", "b c");
+}
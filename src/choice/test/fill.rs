@@ -0,0 +1,96 @@
+use super::*;
+
+#[test]
+fn print_fill_single() {
+    test_fn(vec!["choose", "--fill", "-", "0", "5", "6"], "a b c", "a - -");
+}
+
+#[test]
+fn print_fill_not_needed() {
+    test_fn(vec!["choose", "--fill", "-", "0", "2"], "a b c", "a c");
+}
+
+#[test]
+fn print_fill_negative_single() {
+    test_fn(vec!["choose", "--fill", "NA", "-4", "-1"], "a b c", "NA c");
+}
+
+#[test]
+fn print_fill_range() {
+    test_fn(vec!["choose", "--fill", "-", "1:4"], "a b c", "b c - -");
+}
+
+#[test]
+fn print_fill_exclusive_range() {
+    test_fn(vec!["choose", "--fill", "-", "1..4"], "a b", "b - -");
+}
+
+#[test]
+fn print_fill_reversed_range() {
+    test_fn(vec!["choose", "--fill", "-", "4:1"], "a b c", "- - c b");
+}
+
+#[test]
+fn print_fill_negative_range() {
+    test_fn(vec!["choose", "--fill", "-", "-4:-1"], "a b", "- - a b");
+}
+
+#[test]
+fn print_fill_step() {
+    test_fn(vec!["choose", "--fill", "-", "0:6:2"], "a b c", "a c - -");
+}
+
+#[test]
+fn print_fill_open_range_not_filled() {
+    test_fn(vec!["choose", "--fill", "-", "1:"], "a b c", "b c");
+    test_fn(vec!["choose", "--fill", "-", "1:-1"], "a b c", "b c");
+}
+
+#[test]
+fn print_fill_empty_line() {
+    test_fn(vec!["choose", "--fill", "x", "0", "1"], "", "x x");
+}
+
+#[test]
+fn print_fill_nested() {
+    test_fn(vec!["choose", "--fill", "?", "0/=/1"], "key", "?");
+}
+
+#[test]
+fn print_fill_one_indexed() {
+    test_fn(vec!["choose", "--fill", "-", "--one-indexed", "1", "4"], "a b c", "a -");
+}
+
+#[test]
+fn print_fill_character_wise() {
+    test_fn(vec!["choose", "-c", "--fill", ".", "0:4"], "abc", "abc..");
+}
+
+#[test]
+fn print_fill_format() {
+    test_fn(vec!["choose", "--fill", "0", "--format", "{0}:{3}"], "a b", "a:0");
+}
+
+#[test]
+fn print_fill_huge_range_with_step() {
+    test_fn(
+        vec!["choose", "--fill", "-", "0:99999999999:99999999998"],
+        "a b",
+        "a -",
+    );
+}
+
+#[test]
+fn fill_huge_range_lazily() {
+    let config = Config::from_iter(vec!["choose", "--fill", "-", "0:99999999999"]);
+    let positions = config.opt.choices[0].filled_indices(2).unwrap();
+    assert_eq!((100000000000, Some(100000000000)), positions.size_hint());
+    assert_eq!(vec![Some(0), Some(1), None, None], positions.take(4).collect::<Vec<_>>());
+}
+
+#[test]
+fn fill_huge_reversed_range_lazily() {
+    let config = Config::from_iter(vec!["choose", "--fill", "-", "99999999999:0"]);
+    let positions = config.opt.choices[0].filled_indices(2).unwrap();
+    assert_eq!(vec![None, None], positions.take(2).collect::<Vec<_>>());
+}
@@ -151,10 +151,10 @@ fn error_when_choice_is_isize_min() {
         .get_negative_start_end(slice)
         .unwrap_err();
 
-    if let Error::Config(s) = err {
+    if let Error::Index(s) = err {
         assert!(s.contains("Minimum index value supported is isize::MIN"));
     } else {
-        panic!("Expected Error::Config, found {}", err)
+        panic!("Expected Error::Index, found {}", err)
     }
 }
 
@@ -168,10 +168,10 @@ fn error_when_choice_start_is_isize_min() {
         .get_negative_start_end(slice)
         .unwrap_err();
 
-    if let Error::Config(s) = err {
+    if let Error::Index(s) = err {
         assert!(s.contains("Minimum index value supported is isize::MIN"));
     } else {
-        panic!("Expected Error::Config, found {}", err)
+        panic!("Expected Error::Index, found {}", err)
     }
 }
 
@@ -185,9 +185,9 @@ fn error_when_choice_end_is_isize_min() {
         .get_negative_start_end(slice)
         .unwrap_err();
 
-    if let Error::Config(s) = err {
+    if let Error::Index(s) = err {
         assert!(s.contains("Minimum index value supported is isize::MIN"));
     } else {
-        panic!("Expected Error::Config, found {}", err)
+        panic!("Expected Error::Index, found {}", err)
     }
 }
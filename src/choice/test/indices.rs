@@ -0,0 +1,70 @@
+use super::*;
+
+fn test_fn(vec: Vec<&str>, len: usize, indices: Vec<usize>) {
+    let config = Config::from_iter(vec);
+    assert_eq!(indices, config.opt.choices[0].indices(len).unwrap());
+}
+
+/// Check that the indices of a choice agree with what is printed for it, for
+/// lines of several lengths
+fn test_agrees_with_print(vec: Vec<&str>) {
+    let config = Config::from_iter(vec);
+    for len in 0..8 {
+        let line = (0..len).map(|i| i.to_string()).collect::<Vec<_>>().join(" ");
+        let mut handle = Writer::from(BufWriter::new(MockStdout::new()));
+        process_all_choices_for_line(&mut handle, &config, &line).unwrap();
+
+        let indices = config.opt.choices[0]
+            .indices(len)
+            .unwrap()
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        assert_eq!(MockStdout::str_from_writer(handle), indices, "line: {}", line);
+    }
+}
+
+#[test]
+fn indices_single() {
+    test_fn(vec!["choose", "2"], 5, vec![2]);
+}
+
+#[test]
+fn indices_single_after_end() {
+    test_fn(vec!["choose", "5"], 5, vec![]);
+}
+
+#[test]
+fn indices_range() {
+    test_fn(vec!["choose", "1:3"], 5, vec![1, 2, 3]);
+}
+
+#[test]
+fn indices_reverse_range() {
+    test_fn(vec!["choose", "3:1"], 5, vec![3, 2, 1]);
+}
+
+#[test]
+fn indices_negative_range() {
+    test_fn(vec!["choose", "-3:-1"], 5, vec![2, 3, 4]);
+}
+
+#[test]
+fn indices_empty_line() {
+    test_fn(vec!["choose", ":"], 0, vec![]);
+}
+
+#[test]
+fn indices_agree_with_print() {
+    for choice in [
+        "0", "3", "9", "-1", "-3", "-9", ":", "1:3", "3:1", "2:", ":2", "0:9", "9:0", "-3:-1",
+        "-1:-3", "-2:", ":-2", "1:-2", "5:-3", "-2:1", "-1:0", "-9:-7", "-7:-9", "1..3", "3..1",
+        "1..=3", "3..=1", "-3..-1", "-1..-3", "2..", "..2",
+    ] {
+        test_agrees_with_print(vec!["choose", choice]);
+        test_agrees_with_print(vec!["choose", "-x", choice]);
+        test_agrees_with_print(vec!["choose", "--one-indexed", choice]);
+    }
+}
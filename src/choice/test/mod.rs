@@ -1,14 +1,35 @@
+use crate::choice::Choice;
 use crate::config::Config;
 use crate::opt::Opt;
+use crate::process_all_choices_for_line;
+use crate::result::Result;
 use crate::writer::Writer;
 use std::ffi::OsString;
 use std::io::{self, BufWriter, Write};
 use structopt::StructOpt;
 
 mod get_negative_start_end;
+mod indices;
+mod anchored;
+mod complement;
+mod cut_syntax;
+mod empty_fields;
+mod escape;
+mod fill;
 mod is_reverse_range;
 mod print_choice;
+mod step;
+mod strict;
+mod subscript;
+mod template;
+mod unique_fields;
 mod literal_separators;
+mod multiple_separators;
+mod nested;
+mod predicate;
+mod preset;
+mod quote;
+mod whitespace;
 
 impl Config {
     pub fn from_iter<I>(iter: I) -> Self
@@ -16,10 +37,27 @@ impl Config {
         I: IntoIterator,
         I::Item: Into<OsString> + Clone,
     {
-        Config::new(Opt::from_iter(iter))
+        Config::new(Opt::from_iter(iter)).unwrap()
     }
 }
 
+impl Choice {
+    /// Get the absolute indexes of a choice range based on the slice length
+    fn get_negative_start_end<T>(&self, slice: &[T]) -> Result<Option<(usize, usize)>> {
+        self.negative_start_end(slice.len())
+    }
+}
+
+/// Check that the choices of `vec` print `output` for the line `input`
+fn test_fn(vec: Vec<&str>, input: &str, output: &str) {
+    let config = Config::from_iter(vec);
+    let mut handle = Writer::from(BufWriter::new(MockStdout::new()));
+
+    process_all_choices_for_line(&mut handle, &config, input).unwrap();
+
+    assert_eq!(String::from(output), MockStdout::str_from_writer(handle));
+}
+
 struct MockStdout {
     pub buffer: String,
 }
@@ -43,12 +81,8 @@ impl MockStdout {
 
 impl Write for MockStdout {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let mut bytes_written = 0;
-        for i in buf {
-            self.buffer.push(*i as char);
-            bytes_written += 1;
-        }
-        Ok(bytes_written)
+        self.buffer.push_str(&String::from_utf8_lossy(buf));
+        Ok(buf.len())
     }
 
     fn flush(&mut self) -> io::Result<()> {
@@ -0,0 +1,54 @@
+use super::*;
+
+#[test]
+fn print_repeated_field_separator() {
+    test_fn(vec!["choose", "-f", ",", "-f", ";", "1:2"], "a,b;c", "b c");
+}
+
+#[test]
+fn print_repeated_field_separator_three() {
+    test_fn(
+        vec!["choose", "-f", ",", "-f", ";", "-f", "\\|", "1:3"],
+        "a,b;c|d",
+        "b c d",
+    );
+}
+
+#[test]
+fn print_repeated_field_separator_regex() {
+    test_fn(vec!["choose", "-f", ",", "-f", "-+", "1:2"], "a,b---c", "b c");
+}
+
+#[test]
+fn print_separators() {
+    test_fn(vec!["choose", "--separators", ",;|", "-1"], "a,b;c|d", "d");
+}
+
+#[test]
+fn print_separators_many() {
+    test_fn(vec!["choose", "--separators", ",;|:", "0:3"], "a:b;c|d", "a b c d");
+}
+
+#[test]
+fn print_separators_greedy() {
+    test_fn(vec!["choose", "--separators", ",;", "1"], "a,;b", "b");
+}
+
+#[test]
+fn print_separators_keep_empty_fields() {
+    test_fn(
+        vec!["choose", "--separators", ",;", "--empty-fields", "keep", "1"],
+        "a,;b",
+        "",
+    );
+}
+
+#[test]
+fn print_separators_escaped() {
+    test_fn(vec!["choose", "--separators", ",\\t", "1:2"], "a\tb,c", "b c");
+}
+
+#[test]
+fn print_separators_nested() {
+    test_fn(vec!["choose", "--separators", ",;", "1/=/1"], "a,b=c;d", "c");
+}
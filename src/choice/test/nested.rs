@@ -0,0 +1,67 @@
+use super::*;
+
+#[test]
+fn print_nested_once() {
+    test_fn(vec!["choose", "1/:/0"], "a b:c:d e", "b");
+}
+
+#[test]
+fn print_nested_twice() {
+    test_fn(
+        vec!["choose", "3/:/1/=/-1"],
+        "GET /index 200 host:user=alice:port=80",
+        "alice",
+    );
+}
+
+#[test]
+fn print_nested_negative() {
+    test_fn(vec!["choose", "-1/,/-2"], "a b,c,d", "c");
+}
+
+#[test]
+fn print_nested_range_of_fields() {
+    test_fn(vec!["choose", "1:2/=/1"], "a b=1 c=2 d=3", "1 2");
+}
+
+#[test]
+fn print_nested_range_within_field() {
+    test_fn(vec!["choose", "1/,/2:0"], "a b,c,d e", "d c b");
+}
+
+#[test]
+fn print_nested_with_other_choices() {
+    test_fn(vec!["choose", "0", "1/:/1", "2"], "a b:c d", "a c d");
+}
+
+#[test]
+fn print_nested_with_field_separator() {
+    test_fn(vec!["choose", "-f", ";", "1/ /0"], "a;b c;d", "b");
+}
+
+#[test]
+fn print_nested_regex_separator() {
+    test_fn(vec!["choose", "0/[,;]/2"], "a,b;c", "c");
+}
+
+#[test]
+fn print_nested_exclusive() {
+    test_fn(vec!["choose", "-x", "0/,/0:2"], "a,b,c", "a b");
+}
+
+#[test]
+fn print_nested_one_indexed() {
+    test_fn(vec!["choose", "--one-indexed", "1/,/2"], "a,b,c d", "b");
+}
+
+#[test]
+fn print_nested_out_of_range() {
+    test_fn(vec!["choose", "5/,/0"], "a,b c", "");
+    test_fn(vec!["choose", "0/,/5"], "a,b c", "");
+}
+
+#[test]
+fn print_nested_non_greedy() {
+    test_fn(vec!["choose", "-n", "0/,/1"], "a,,b", "");
+    test_fn(vec!["choose", "0/,/1"], "a,,b", "b");
+}
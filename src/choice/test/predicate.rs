@@ -0,0 +1,108 @@
+use crate::error::Error;
+
+use super::*;
+
+fn test_fn(vec: Vec<&str>, input: &[&str], output: &str) {
+    let config = Config::from_iter(vec);
+    let mut handle = Writer::from(BufWriter::new(MockStdout::new()));
+
+    for line in input.iter().filter(|line| config.matches(line)) {
+        process_all_choices_for_line(&mut handle, &config, line).unwrap();
+        handle.write_line().unwrap();
+    }
+
+    assert_eq!(String::from(output), MockStdout::str_from_writer(handle));
+}
+
+const LOG: &[&str] = &[
+    "GET /index.html 200 5120",
+    "POST /login 302 120",
+    "GET /missing 404 310",
+    "GET /large.iso 200 73400320",
+];
+
+#[test]
+fn print_where_numeric() {
+    test_fn(vec!["choose", "--where", "3>500", "1"], LOG, "/index.html\n/large.iso");
+}
+
+#[test]
+fn print_where_regex() {
+    test_fn(vec!["choose", "--where", "1=~^/l", "0"], LOG, "POST\nGET");
+}
+
+#[test]
+fn print_where_negative_index() {
+    test_fn(vec!["choose", "--where", "-2!=200", "1"], LOG, "/login\n/missing");
+}
+
+#[test]
+fn print_where_and() {
+    test_fn(vec!["choose", "--where", "0==GET && 2<400", "1"], LOG, "/index.html\n/large.iso");
+}
+
+#[test]
+fn print_where_or() {
+    test_fn(vec!["choose", "--where", "0==POST || 2==404", "1"], LOG, "/login\n/missing");
+}
+
+#[test]
+fn print_where_repeated() {
+    test_fn(
+        vec!["choose", "--where", "0==GET", "--where", "3<1000", "1"],
+        LOG,
+        "/missing",
+    );
+}
+
+#[test]
+fn print_where_one_indexed() {
+    test_fn(vec!["choose", "--one-indexed", "--where", "3==302", "2"], LOG, "/login");
+}
+
+#[test]
+fn print_where_missing_field() {
+    test_fn(vec!["choose", "--where", "4!=x", "0"], LOG, "");
+}
+
+#[test]
+fn print_where_field_separator() {
+    test_fn(vec!["choose", "-f", ":", "--where", "2>=1000", "0"], &["root:x:0", "me:x:1000"], "me");
+}
+
+const PASSWD: &[&str] = &["root:x:0:0::/root:/bin/bash", "daemon:x:1:1::/:/usr/sbin/nologin"];
+
+#[test]
+fn where_field_name() {
+    test_fn(vec!["choose", "--preset", "passwd", "--where", "shell=/bin/bash", "user"], PASSWD, "root");
+}
+
+#[test]
+fn where_field_names_and_indexes() {
+    test_fn(vec!["choose", "--preset", "passwd", "--where", "uid>0 && 7=~nologin", "user"], PASSWD, "daemon");
+}
+
+#[test]
+fn where_field_name_zero_indexed_preset() {
+    test_fn(
+        vec!["choose", "--preset", "ls-l", "--where", "size>100", "name"],
+        &[
+            "-rw-r--r--  1 user staff  1234 Jan  1 12:00 big file",
+            "-rw-r--r--  1 user staff    12 Jan  1 12:00 small file",
+        ],
+        "big file",
+    );
+}
+
+#[test]
+fn where_unknown_field_name() {
+    let opt = Opt::from_iter(vec!["choose", "--preset", "passwd", "--where", "shel=x", "0"]);
+    let e = Config::new(opt).err().unwrap();
+    assert!(e.to_string().contains("unknown field shel in --where; the passwd preset names the fields user"));
+}
+
+#[test]
+fn where_field_name_without_preset() {
+    let e = Config::new(Opt::from_iter(vec!["choose", "--where", "shell=x", "0"])).err().unwrap();
+    assert!(matches!(e, Error::Config(_)));
+}
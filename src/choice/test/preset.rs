@@ -0,0 +1,134 @@
+use crate::error::Error;
+
+use super::*;
+
+const PASSWD: &str = "root:x:0:0::/root:/bin/bash";
+const APACHE: &str = r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /a.gif HTTP/1.0" 200 2326 "http://example.com/" "Mozilla/4.08 (Win98)""#;
+
+#[test]
+fn passwd_field_names() {
+    test_fn(vec!["choose", "--preset", "passwd", "user", "shell"], PASSWD, "root:/bin/bash");
+}
+
+#[test]
+fn passwd_one_indexed() {
+    test_fn(vec!["choose", "--preset", "passwd", "1", "7"], PASSWD, "root:/bin/bash");
+}
+
+#[test]
+fn passwd_keeps_empty_fields() {
+    test_fn(vec!["choose", "--preset", "passwd", "gecos:home"], PASSWD, ":/root");
+}
+
+#[test]
+fn passwd_range_of_names() {
+    test_fn(vec!["choose", "--preset", "passwd", "uid:gid,!password"], PASSWD, "0:0");
+}
+
+#[test]
+fn command_line_overrides_preset() {
+    test_fn(vec!["choose", "--preset", "passwd", "-o", " ", "user", "shell"], PASSWD, "root /bin/bash");
+}
+
+#[test]
+fn cut_syntax_with_preset() {
+    test_fn(vec!["choose", "--preset", "passwd", "--cut-syntax", "uid-gid"], PASSWD, "0:0");
+}
+
+#[test]
+fn format_with_field_names() {
+    test_fn(vec!["choose", "--preset", "passwd", "--format", "{user} uses {shell}"], PASSWD, "root uses /bin/bash");
+}
+
+#[test]
+fn csv_quoted_fields() {
+    test_fn(vec!["choose", "--preset", "csv", "2", "3"], "a,\"b,c\",d", "\"b,c\",d");
+}
+
+#[test]
+fn csv_doubled_quotes() {
+    test_fn(vec!["choose", "--preset", "csv", "1"], "\"say \"\"hi, there\"\"\",b", "\"say \"\"hi, there\"\"\"");
+}
+
+#[test]
+fn csv_separator_on_command_line_drops_quotes() {
+    test_fn(vec!["choose", "--preset", "csv", "-f", ";", "2"], "a;\"b;c\"", "\"b");
+}
+
+#[test]
+fn tsv() {
+    test_fn(vec!["choose", "--preset", "tsv", "3", "4"], "a\tb\t\td", "\td");
+}
+
+#[test]
+fn apache_combined() {
+    test_fn(
+        vec!["choose", "--preset", "apache-combined", "-o", "|", "status", "request", "agent"],
+        APACHE,
+        "200|GET /a.gif HTTP/1.0|Mozilla/4.08 (Win98)",
+    );
+}
+
+#[test]
+fn apache_common_has_empty_referer_and_agent() {
+    let common = r#"127.0.0.1 - - [10/Oct/2000:13:55:36 -0700] "GET / HTTP/1.0" 404 -"#;
+    test_fn(vec!["choose", "--preset", "apache-combined", "-o", "|", "status:-1"], common, "404|-||");
+}
+
+#[test]
+fn pattern_not_matching_line_has_no_fields() {
+    test_fn(vec!["choose", "--preset", "nginx", "status"], "not a log line", "");
+}
+
+#[test]
+fn syslog() {
+    test_fn(
+        vec!["choose", "--preset", "syslog", "-o", "|", "program", "pid", "message"],
+        "Oct 11 22:14:15 mymachine su[230]: 'su root' failed",
+        "su|230|'su root' failed",
+    );
+}
+
+#[test]
+fn syslog_without_pid() {
+    test_fn(
+        vec!["choose", "--preset", "syslog", "-o", "|", "host:-1"],
+        "Oct  1 02:04:05 host kernel: eth0 up",
+        "host|kernel||eth0 up",
+    );
+}
+
+#[test]
+fn ls_l_name_with_spaces() {
+    test_fn(
+        vec!["choose", "--preset", "ls-l", "-o", "|", "mode", "size", "name"],
+        "-rw-r--r--  1 user staff  1234 Jan  1 12:00 my file.txt",
+        "-rw-r--r--|1234|my file.txt",
+    );
+}
+
+#[test]
+fn ps_command_with_arguments() {
+    test_fn(
+        vec!["choose", "--preset", "ps", "-o", "|", "pid", "command"],
+        "root         1  0.0  0.1 167744 11520 ?        Ss   Jan01   0:05 /sbin/init splash",
+        "1|/sbin/init splash",
+    );
+}
+
+#[test]
+fn separator_on_command_line_replaces_pattern() {
+    test_fn(vec!["choose", "--preset", "ps", "-f", ",", "1"], "a,b", "b");
+}
+
+#[test]
+fn unknown_preset() {
+    let e = Config::new(Opt::from_iter(vec!["choose", "--preset", "nope", "0"])).err().unwrap();
+    assert!(matches!(e, Error::Config(_)));
+}
+
+#[test]
+fn unknown_field_name_suggests_names() {
+    let e = Config::new(Opt::from_iter(vec!["choose", "--preset", "group", "gidd"])).err().unwrap();
+    assert!(e.to_string().ends_with("help: the group preset names the fields group, password, gid, members"));
+}
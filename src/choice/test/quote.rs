@@ -0,0 +1,39 @@
+use crate::error::Error;
+
+use super::*;
+
+#[test]
+fn print_quoted_comma() {
+    test_fn(vec!["choose", "-f", ",", "--quote", "\"", "1"], "a,\"b,c\",d", "\"b,c\"");
+}
+
+#[test]
+fn print_quoted_range() {
+    test_fn(
+        vec!["choose", "-f", ",", "--quote", "\"", "-o", ",", "1:"],
+        "a,\"b,c\",d",
+        "\"b,c\",d",
+    );
+}
+
+#[test]
+fn print_quoted_negative_index() {
+    test_fn(vec!["choose", "-f", ",", "--quote", "'", "-2"], "a,'b,c',d", "'b,c'");
+}
+
+#[test]
+fn print_quoted_string_separator() {
+    test_fn(vec!["choose", "-f", "::", "--quote", "\"", "0"], "\"a::b\"::c", "\"a::b\"");
+}
+
+#[test]
+fn quote_requires_literal_separator() {
+    let e = Config::new(Opt::from_iter(vec!["choose", "--quote", "\"", "0"])).err().unwrap();
+    assert!(matches!(e, Error::Config(_)));
+}
+
+#[test]
+fn quote_conflicts_with_escape() {
+    let opt = Opt::from_iter_safe(vec!["choose", "-f", ",", "--quote", "\"", "--escape", "\\", "0"]);
+    assert!(opt.is_err());
+}
@@ -0,0 +1,91 @@
+use super::*;
+
+#[test]
+fn print_every_other() {
+    test_fn(vec!["choose", "0:10:2"], "a b c d e f", "a c e");
+}
+
+#[test]
+fn print_every_third_open_range() {
+    test_fn(vec!["choose", "1::3"], "a b c d e f g h", "b e h");
+}
+
+#[test]
+fn print_reversed_negative_step() {
+    test_fn(vec!["choose", "::-1"], "a b c d e f", "f e d c b a");
+}
+
+#[test]
+fn print_negative_step_from_end() {
+    test_fn(vec!["choose", "0:-1:-2"], "a b c d e f", "f d b");
+}
+
+#[test]
+fn print_reverse_range_step() {
+    test_fn(vec!["choose", "5:0:2"], "a b c d e f", "f d b");
+}
+
+#[test]
+fn print_reverse_range_negative_step() {
+    test_fn(vec!["choose", "5:0:-2"], "a b c d e f", "a c e");
+}
+
+#[test]
+fn print_negative_index_step() {
+    test_fn(vec!["choose", "-3::2"], "a b c d e f", "d f");
+}
+
+#[test]
+fn print_step_exclusive() {
+    test_fn(vec!["choose", "-x", "1:4:2"], "a b c d e f", "b d");
+}
+
+#[test]
+fn print_step_one_indexed() {
+    test_fn(vec!["choose", "--one-indexed", "1:5:2"], "a b c d e f", "a c e");
+}
+
+#[test]
+fn print_step_rust_exclusive_range() {
+    test_fn(vec!["choose", "0..5:2"], "a b c d e f", "a c e");
+}
+
+#[test]
+fn print_step_rust_inclusive_range() {
+    test_fn(vec!["choose", "0..=4:-2"], "a b c d e f", "e c a");
+}
+
+#[test]
+fn print_step_larger_than_range() {
+    test_fn(vec!["choose", "1:3:5"], "a b c d e f", "b");
+}
+
+#[test]
+fn print_step_after_end() {
+    test_fn(vec!["choose", "9::2"], "a b c", "");
+}
+
+#[test]
+fn print_step_character_wise() {
+    test_fn(vec!["choose", "-c", "::2"], "abcdef", "ace");
+}
+
+#[test]
+fn print_step_character_wise_reversed() {
+    test_fn(vec!["choose", "-c", "-o", ",", "::-3"], "abcdefg", "g,d,a");
+}
+
+#[test]
+fn print_step_with_other_choices() {
+    test_fn(vec!["choose", "0", "1::2"], "a b c d e", "a b d");
+}
+
+#[test]
+fn print_python_negative_step_as_reversed_range() {
+    test_fn(vec!["choose", "5:1:2"], "a b c d e f", "f d b");
+}
+
+#[test]
+fn print_python_negative_step_open_start_as_reversed_range() {
+    test_fn(vec!["choose", "-3:0"], "a b c d e f", "d c b a");
+}
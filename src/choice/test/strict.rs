@@ -0,0 +1,138 @@
+use crate::error::Error;
+use crate::strict::{self, Strictness};
+
+use super::*;
+
+fn satisfied(vec: Vec<&str>, input: &str) -> bool {
+    let config = Config::from_iter(vec);
+    let fields = config.fields(input);
+    config.opt.choices[0].is_satisfied_by(&fields, &config).unwrap()
+}
+
+#[test]
+fn single_in_range() {
+    assert!(satisfied(vec!["choose", "2"], "a b c"));
+    assert!(satisfied(vec!["choose", "-3"], "a b c"));
+}
+
+#[test]
+fn single_out_of_range() {
+    assert!(!satisfied(vec!["choose", "3"], "a b c"));
+    assert!(!satisfied(vec!["choose", "-4"], "a b c"));
+}
+
+#[test]
+fn bounded_range_partly_out_of_range() {
+    assert!(satisfied(vec!["choose", "0:2"], "a b c"));
+    assert!(!satisfied(vec!["choose", "1:3"], "a b c"));
+    assert!(!satisfied(vec!["choose", "-4:-2"], "a b c"));
+}
+
+#[test]
+fn huge_bounded_range() {
+    assert!(!satisfied(vec!["choose", "0:99999999999"], "a b c"));
+    assert!(!satisfied(vec!["choose", "99999999999:0"], "a b c"));
+    assert!(!satisfied(vec!["choose", "-99999999999:-1"], "a b c"));
+}
+
+#[test]
+fn stepped_range_within_line() {
+    assert!(satisfied(vec!["choose", "0:3:2"], "a b c"));
+    assert!(!satisfied(vec!["choose", "0:4:2"], "a b c"));
+    assert!(satisfied(vec!["choose", "0:3:-2"], "a b c d"));
+    assert!(!satisfied(vec!["choose", "0:3:-2"], "a b c"));
+    assert!(satisfied(vec!["choose", "3:0:2"], "a b c d"));
+    assert!(satisfied(vec!["choose", "0:99999999999:100000000000"], "a"));
+    assert!(!satisfied(vec!["choose", "0:99999999999:99999999998"], "a"));
+}
+
+#[test]
+fn open_range() {
+    assert!(satisfied(vec!["choose", "2:"], "a b c"));
+    assert!(!satisfied(vec!["choose", "3:"], "a b c"));
+}
+
+#[test]
+fn empty_range() {
+    assert!(!satisfied(vec!["choose", "-x", "1:1"], "a b c"));
+}
+
+#[test]
+fn empty_line() {
+    assert!(!satisfied(vec!["choose", ":"], ""));
+}
+
+#[test]
+fn anchored() {
+    assert!(satisfied(vec!["choose", "@/b/+1"], "a b c"));
+    assert!(!satisfied(vec!["choose", "@/c/+1"], "a b c"));
+    assert!(!satisfied(vec!["choose", "@/z/0"], "a b c"));
+}
+
+#[test]
+fn nested() {
+    assert!(satisfied(vec!["choose", "0/:/1"], "a:b c"));
+    assert!(!satisfied(vec!["choose", "0/:/5"], "a:b c"));
+    assert!(!satisfied(vec!["choose", "0:1/:/1"], "a:b c"));
+}
+
+#[test]
+fn subscript() {
+    assert!(satisfied(vec!["choose", "0[1]"], "ab c"));
+    assert!(!satisfied(vec!["choose", "0[5]"], "ab c"));
+    assert!(!satisfied(vec!["choose", "0/:/0[2]"], "ab:c"));
+}
+
+#[test]
+fn check_line_nested() {
+    let config = Config::from_iter(vec!["choose", "--strict", "0/:/5"]);
+    assert!(matches!(
+        strict::check_line(&config, "a:b c", 1, Strictness::Fail),
+        Err(Error::Strict(_))
+    ));
+}
+
+#[test]
+fn check_line_huge_range() {
+    let config = Config::from_iter(vec!["choose", "--strict", "0:99999999999"]);
+    assert!(matches!(
+        strict::check_line(&config, "a b", 1, Strictness::Fail),
+        Err(Error::Strict(_))
+    ));
+}
+
+#[test]
+fn check_line_fail() {
+    let config = Config::from_iter(vec!["choose", "--strict", "0", "3"]);
+    match strict::check_line(&config, "a b c", 7, Strictness::Fail) {
+        Err(Error::Strict(e)) => assert_eq!("line 7: choice 3 cannot be satisfied by 3 fields", e),
+        r => panic!("expected a strict error, got {:?}", r),
+    }
+}
+
+#[test]
+fn check_line_warn() {
+    let config = Config::from_iter(vec!["choose", "--strict=warn", "0", "3"]);
+    assert!(strict::check_line(&config, "a b c", 7, Strictness::Warn).is_ok());
+}
+
+#[test]
+fn check_line_ignores_excluded() {
+    let config = Config::from_iter(vec!["choose", "--strict", "0", "!5"]);
+    assert!(strict::check_line(&config, "a b c", 1, Strictness::Fail).is_ok());
+}
+
+#[test]
+fn check_line_format() {
+    let config = Config::from_iter(vec!["choose", "--strict", "--format", "{0}:{1}"]);
+    assert!(strict::check_line(&config, "a", 1, Strictness::Fail).is_err());
+}
+
+#[test]
+fn parse_strictness() {
+    assert_eq!(Some(None), Opt::from_iter(vec!["choose", "--strict", "0"]).strict);
+    assert_eq!(
+        Some(Some(Strictness::Warn)),
+        Opt::from_iter(vec!["choose", "--strict=warn", "0"]).strict
+    );
+}
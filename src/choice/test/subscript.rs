@@ -0,0 +1,76 @@
+use super::*;
+
+#[test]
+fn print_subscript_prefix() {
+    test_fn(
+        vec!["choose", "1[0:6]"],
+        "commit 3f2a9c81be04d7e1 Fix tests",
+        "3f2a9c8",
+    );
+}
+
+#[test]
+fn print_subscript_negative() {
+    test_fn(vec!["choose", "-1[-4:]"], "a 2024-01-15", "1-15");
+}
+
+#[test]
+fn print_subscript_each_field_of_range() {
+    test_fn(vec!["choose", "0:2[0]"], "apple banana cherry", "a b c");
+}
+
+#[test]
+fn print_subscript_with_other_choices() {
+    test_fn(vec!["choose", "0", "1[:3]", "2"], "x 2024-01-15T10:00 y", "x 2024 y");
+}
+
+#[test]
+fn print_subscript_exclusive() {
+    test_fn(vec!["choose", "-x", "0[0:2]"], "abcdef", "ab");
+    test_fn(vec!["choose", "0[0..2]"], "abcdef", "ab");
+}
+
+#[test]
+fn print_subscript_one_indexed() {
+    test_fn(vec!["choose", "--one-indexed", "1[1:3]"], "abcdef", "abc");
+}
+
+#[test]
+fn print_subscript_reversed() {
+    test_fn(vec!["choose", "0[::-1]"], "abc def", "cba");
+}
+
+#[test]
+fn print_subscript_out_of_range() {
+    test_fn(vec!["choose", "0[2:10]", "1[5]"], "abcd ef", "cd");
+}
+
+#[test]
+fn print_subscript_out_of_range_before_other_field() {
+    test_fn(vec!["choose", "0[5]", "1"], "ab cd", "cd");
+}
+
+#[test]
+fn print_subscript_out_of_range_keeping_empty_fields() {
+    test_fn(vec!["choose", "-n", "0", "1[5]", "0"], "ab cd", "ab ab");
+}
+
+#[test]
+fn print_subscript_multibyte() {
+    test_fn(vec!["choose", "0[1:2]"], "héllo", "él");
+}
+
+#[test]
+fn print_subscript_nested() {
+    test_fn(vec!["choose", "1/=/1[0:2]"], "set key=value", "val");
+}
+
+#[test]
+fn print_subscript_field_separator() {
+    test_fn(vec!["choose", "-f", ",", "1[:3]", "0"], "x,2024-01-15", "2024 x");
+}
+
+#[test]
+fn print_subscript_cut_syntax() {
+    test_fn(vec!["choose", "--cut-syntax", "2[1-4]"], "x 2024-01-15", "2024");
+}
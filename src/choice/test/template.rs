@@ -0,0 +1,62 @@
+use super::*;
+
+#[test]
+fn print_format_fields() {
+    test_fn(vec!["choose", "--format", "{0}@{2}:{-1}"], "root example.com ssh 22", "root@ssh:22");
+}
+
+#[test]
+fn print_format_range_joined_with_output_separator() {
+    test_fn(vec!["choose", "--format", "[{1:2}]", "-o", ","], "a b c d", "[b,c]");
+}
+
+#[test]
+fn print_format_comma_choices() {
+    test_fn(vec!["choose", "--format", "<{0,3}>"], "a b c d", "<a d>");
+}
+
+#[test]
+fn print_format_escaped_braces() {
+    test_fn(vec!["choose", "--format", "{{{1}}}"], "a b c", "{b}");
+}
+
+#[test]
+fn print_format_width_and_alignment() {
+    test_fn(vec!["choose", "--format", "|{0:>5}|{1:<4}|{2:*^7}|"], "ab cd ef", "|   ab|cd  |**ef***|");
+}
+
+#[test]
+fn print_format_number_defaults_right_aligned() {
+    test_fn(vec!["choose", "--format", "{0:<1}|{1:6}"], "x 42", "x|42");
+    test_fn(vec!["choose", "--format", "{0:<1}|{1:>6}"], "x 42", "x|    42");
+}
+
+#[test]
+fn print_format_precision() {
+    test_fn(vec!["choose", "--format", "{0:.2} {1:.3}"], "3.14159 abcdef", "3.14 abc");
+}
+
+#[test]
+fn print_format_zero_padding() {
+    test_fn(vec!["choose", "--format", "{0:06.1}"], "-3.14159", "-003.1");
+}
+
+#[test]
+fn print_format_missing_field() {
+    test_fn(vec!["choose", "--format", "{0}-{5}-{1}"], "a b", "a--b");
+}
+
+#[test]
+fn print_format_one_indexed() {
+    test_fn(vec!["choose", "--one-indexed", "--format", "{1}={2}"], "a b", "a=b");
+}
+
+#[test]
+fn print_format_nested() {
+    test_fn(vec!["choose", "--format", "{1/=/1}"], "set key=value", "value");
+}
+
+#[test]
+fn print_format_field_separator() {
+    test_fn(vec!["choose", "-f", ":", "--format", "{0} has uid {2}"], "root:x:0:0", "root has uid 0");
+}
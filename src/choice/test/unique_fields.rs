@@ -0,0 +1,74 @@
+use super::*;
+
+#[test]
+fn print_overlapping_without_unique_fields() {
+    test_fn(vec!["choose", "0:3", "2:5"], "a b c d e f g", "a b c d c d e f");
+}
+
+#[test]
+fn print_unique_overlapping_ranges() {
+    test_fn(vec!["choose", "--unique-fields", "0:3", "2:5"], "a b c d e f g", "a b c d e f");
+}
+
+#[test]
+fn print_unique_first_mention_order() {
+    test_fn(vec!["choose", "--unique-fields", "4", "1:5"], "a b c d e f g", "e b c d f");
+}
+
+#[test]
+fn print_unique_ascending_order() {
+    test_fn(
+        vec!["choose", "--unique-fields", "--unique-order", "ascending", "4", "1:5"],
+        "a b c d e f g",
+        "b c d e f",
+    );
+}
+
+#[test]
+fn print_unique_reversed_range() {
+    test_fn(vec!["choose", "--unique-fields", "3:1", "2"], "a b c d e", "d c b");
+}
+
+#[test]
+fn print_unique_negative_indices() {
+    test_fn(vec!["choose", "--unique-fields", "-1", "3:"], "a b c d e", "e d");
+}
+
+#[test]
+fn print_unique_same_choice_twice() {
+    test_fn(vec!["choose", "--unique-fields", "1", "1", "-4"], "a b c d e", "b");
+}
+
+#[test]
+fn print_unique_excluded() {
+    test_fn(vec!["choose", "--unique-fields", "0:3", "1:4", "!2"], "a b c d e", "a b d e");
+}
+
+#[test]
+fn print_unique_complement() {
+    test_fn(vec!["choose", "--unique-fields", "--complement", "1", "1:2"], "a b c d", "a d");
+}
+
+#[test]
+fn print_unique_out_of_range() {
+    test_fn(vec!["choose", "--unique-fields", "3:10", "5"], "a b c d e", "d e");
+}
+
+#[test]
+fn print_unique_fields_fill() {
+    test_fn(vec!["choose", "--fill", "-", "--unique-fields", "0", "5"], "a b", "a -");
+}
+
+#[test]
+fn print_unique_fields_fill_missing_field_once() {
+    test_fn(vec!["choose", "--fill", "-", "--unique-fields", "5", "0:5"], "a b", "- a b - - -");
+}
+
+#[test]
+fn print_unique_fields_fill_ascending() {
+    test_fn(
+        vec!["choose", "--fill", "-", "--unique-fields", "--unique-order", "ascending", "3", "1", "0"],
+        "a b",
+        "a b -",
+    );
+}
@@ -0,0 +1,40 @@
+use super::*;
+
+#[test]
+fn print_unicode_whitespace_default() {
+    test_fn(vec!["choose", "1"], "a b\u{a0}c d", "b");
+}
+
+#[test]
+fn print_ascii_whitespace() {
+    test_fn(vec!["choose", "--ascii-whitespace", "1"], "a b\u{a0}c d", "b\u{a0}c");
+}
+
+#[test]
+fn print_ascii_whitespace_collapses_runs() {
+    test_fn(
+        vec!["choose", "--ascii-whitespace", "0:2"],
+        "  a \t b\n\n c  ",
+        "a b c",
+    );
+}
+
+#[test]
+fn print_ascii_whitespace_negative() {
+    test_fn(vec!["choose", "--ascii-whitespace", "-1"], "a b\u{3000}c ", "b\u{3000}c");
+}
+
+#[test]
+fn print_whitespace_chars() {
+    test_fn(vec!["choose", "--whitespace-chars", " _", "1:2"], "a__b _ c d", "b c");
+}
+
+#[test]
+fn print_whitespace_chars_escaped() {
+    test_fn(vec!["choose", "--whitespace-chars", "\\t", "1"], "a b\t\tc", "c");
+}
+
+#[test]
+fn print_whitespace_chars_ignores_non_greedy() {
+    test_fn(vec!["choose", "--whitespace-chars", ",", "-n", "1"], ",a,,b", "b");
+}
@@ -0,0 +1,53 @@
+use crate::config::Config;
+use crate::result::Result;
+use crate::writer::{WriteReceiver, Writer};
+
+/// Print the choices for a line where at least one choice excludes fields
+///
+/// The fields covered by excluded choices are resolved against the number of
+/// fields in the line. If every choice is excluded, all other fields are
+/// printed in their original order; otherwise the remaining choices are
+/// printed as usual, skipping any excluded field and filling any missing one
+/// with `--fill`.
+pub fn print_line<WR: WriteReceiver>(
+    handle: &mut Writer<WR>,
+    config: &Config,
+    line: &str,
+) -> Result<()> {
+    let fields = config.fields(line);
+
+    let mut excluded = vec![false; fields.len()];
+    for choice in config.opt.choices.iter().filter(|c| c.excluded) {
+        for i in choice.resolve(&fields)? {
+            excluded[i] = true;
+        }
+    }
+
+    let mut included = config.opt.choices.iter().filter(|c| !c.excluded).peekable();
+    if included.peek().is_none() {
+        for (field, _) in fields.iter().zip(&excluded).filter(|(_, e)| !**e) {
+            handle.write_choice_separable(field.as_ref(), config)?;
+        }
+    } else {
+        for choice in included {
+            match choice.fill(config) {
+                Some(fill) => {
+                    for (_, i) in choice.filled_positions(fields.len())? {
+                        match i {
+                            Some(i) if excluded[i] => (),
+                            Some(i) => choice.print_fields_at(Some(i), &fields, config, handle)?,
+                            None => handle.write_choice_separable(fill, config)?,
+                        }
+                    }
+                }
+                None => {
+                    let indices = choice.resolve(&fields)?;
+                    let indices = indices.into_iter().filter(|i| !excluded[*i]);
+                    choice.print_fields_at(indices, &fields, config, handle)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,76 +1,218 @@
 use regex::Regex;
-use std::process;
+use std::borrow::Cow;
 
-use crate::choice::ChoiceKind;
+use crate::char_set::CharSet;
+use crate::choice::{Choice, ChoiceKind};
+use crate::empty_fields::EmptyFields;
+use crate::error::Error;
+use crate::escape::EscapedSplit;
 use crate::opt::Opt;
+use crate::parse;
+use crate::parse_error::{ParseError, ParseErrorKind};
+use crate::predicate::Predicate;
+use crate::preset::Preset;
+use crate::quote::QuotedSplit;
+use crate::template::Template;
 
+#[derive(Debug)]
 pub enum Separator {
     Whitespace,
+    AsciiWhitespace,
+    WhitespaceChars(Vec<char>),
     LiteralChar(char),
+    LiteralStr(String),
+    LiteralChars(CharSet),
     Regex(Regex),
+    /// The fields of a line are the capture groups of a regex matching it
+    Pattern(Regex),
+}
+
+impl Separator {
+    /// Build a separator from a Rust `regex` pattern, avoiding regex where the
+    /// pattern would only match literal text
+    pub fn new(s: &str) -> Result<Self, regex::Error> {
+        match regex_is_literal_char(s) {
+            Some(c) => Ok(Separator::LiteralChar(c)),
+            None if regex_is_literal_str(s) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(Separator::LiteralChar(c)),
+                    _ => Ok(Separator::LiteralStr(s.to_string())),
+                }
+            }
+            None => Regex::new(s).map(Separator::Regex),
+        }
+    }
+
+    /// Build a separator that matches any one of several Rust `regex` patterns
+    ///
+    /// If every pattern would only match a single literal char, the separator
+    /// is a set of chars rather than a regex alternation.
+    pub fn any(patterns: &[String]) -> Result<Self, regex::Error> {
+        if let [pattern] = patterns {
+            return Separator::new(pattern);
+        }
+
+        let mut chars = Vec::new();
+        for pattern in patterns {
+            match Separator::new(pattern)? {
+                Separator::LiteralChar(c) => chars.push(c),
+                _ => {
+                    let alternation = patterns
+                        .iter()
+                        .map(|p| format!("(?:{})", p))
+                        .collect::<Vec<_>>()
+                        .join("|");
+                    return Regex::new(&alternation).map(Separator::Regex);
+                }
+            }
+        }
+
+        Ok(Separator::LiteralChars(CharSet::new(&chars)))
+    }
+
+    /// Split a line into all of its fields
+    ///
+    /// The whitespace separators always collapse runs of whitespace, so they
+    /// never produce empty fields for the empty field policy to apply to. A
+    /// pattern always gives a field for each capture group, empty if the group
+    /// did not take part in the match, and no fields for a line it does not
+    /// match.
+    pub fn split<'a>(&self, line: &'a str, empty_fields: EmptyFields) -> Vec<&'a str> {
+        match self {
+            Separator::LiteralChar(c) => empty_fields.filter(line.split(*c)).collect(),
+            Separator::LiteralStr(s) => empty_fields.filter(line.split(s.as_str())).collect(),
+            Separator::LiteralChars(set) => empty_fields.filter(set.split(line)).collect(),
+            Separator::Regex(r) => empty_fields.filter(r.split(line)).collect(),
+            Separator::Whitespace => line.split_whitespace().collect(),
+            Separator::AsciiWhitespace => line.split_ascii_whitespace().collect(),
+            Separator::WhitespaceChars(chars) => line
+                .split(chars.as_slice())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            Separator::Pattern(r) => match r.captures(line) {
+                Some(captures) => captures.iter().skip(1).map(|m| m.map_or("", |m| m.as_str())).collect(),
+                None => Vec::new(),
+            },
+        }
+    }
 }
 
 pub struct Config {
     pub opt: Opt,
     pub separator: Separator,
     pub output_separator: Box<[u8]>,
+    pub empty_fields: EmptyFields,
+    pub excluding: bool,
+    pub template: Option<Template>,
 }
 
 impl Config {
-    pub fn new(mut opt: Opt) -> Self {
-        for choice in &mut opt.choices {
-            if (opt.exclusive && choice.kind == ChoiceKind::ColonRange)
-                || choice.kind == ChoiceKind::RustExclusiveRange
-            {
-                if choice.is_reverse_range() {
-                    choice.start -= 1;
-                } else {
-                    choice.end -= 1;
-                }
+    pub fn new(mut opt: Opt) -> Result<Self, Error> {
+        let preset = match &opt.preset {
+            Some(name) => Some(Preset::find(name, &opt.presets)?),
+            None => None,
+        };
+        if let Some(preset) = &preset {
+            preset.apply(&mut opt);
+        }
+
+        // replace the names of the preset's fields with their indexes
+        let one_based = opt.one_indexed || opt.cut_syntax;
+        let resolve = |src: &str| match &preset {
+            Some(preset) => preset.resolve(src, one_based),
+            None => src.to_string(),
+        };
+        let suggest = |e| match &preset {
+            Some(preset) => preset.suggest(e),
+            None => e,
+        };
+
+        for arg in &opt.choice_args {
+            let arg = resolve(arg);
+            let choices = parse::choices(&arg, opt.cut_syntax)
+                .map_err(|e| Error::Parse("<choices>...", suggest(e)))?;
+
+            // --complement excludes every choice, which a nested choice cannot be
+            let nested = choices.iter().find(|c| c.nested.is_some());
+            if let Some(choice) = nested.filter(|_| opt.complement) {
+                let kind = ParseErrorKind::Nested("a nested choice cannot be excluded by --complement");
+                let start = arg.find(&choice.source).unwrap_or(0);
+                let e = ParseError::new(kind, &arg, start..start + choice.source.len());
+                return Err(Error::Parse("<choices>...", e));
             }
 
-            if opt.one_indexed {
-                if choice.start > 0 {
-                    choice.start -= 1;
-                }
+            opt.choices.extend(choices);
+        }
 
-                if choice.end > 0 {
-                    choice.end -= 1;
-                }
+        for choice in &mut opt.choices {
+            adjust_choice(choice, opt.exclusive, opt.one_indexed);
+            if opt.complement {
+                choice.excluded = true;
             }
         }
 
-        let separator = match &opt.field_separator {
-            Some(s) => {
-                match regex_is_literal_char(s) {
-                    Some(c) => Separator::LiteralChar(c),
-                    None => match Regex::new(s) {
-                        Ok(r) => Separator::Regex(r),
-                        Err(e) => {
-                            // Exit code of 2 means failed to compile field_separator regex
-                            match e {
-                                regex::Error::Syntax(e) => {
-                                    eprintln!("Syntax error compiling regular expression: {}", e);
-                                    process::exit(2);
-                                }
-                                regex::Error::CompiledTooBig(e) => {
-                                    eprintln!(
-                                        "Compiled regular expression too big: compiled size cannot exceed {} bytes",
-                                        e
-                                    );
-                                    process::exit(2);
-                                }
-                                _ => {
-                                    eprintln!("Error compiling regular expression: {}", e);
-                                    process::exit(2);
-                                }
-                            }
-                        }
-                    },
+        let mut template = match &opt.format {
+            Some(src) => Some(
+                parse::template(&resolve(src), opt.cut_syntax)
+                    .map_err(|e| Error::Parse("--format <format>", suggest(e)))?,
+            ),
+            None => None,
+        };
+
+        for choice in template.iter_mut().flat_map(Template::choices_mut) {
+            adjust_choice(choice, opt.exclusive, opt.one_indexed);
+        }
+
+        if opt.one_indexed || opt.cut_syntax {
+            opt.predicates.iter_mut().for_each(Predicate::one_indexed);
+        }
+
+        // names are resolved after the indexes are made zero-based, as the
+        // indexes of a preset's fields already are
+        let index_of = |name: &str| preset.as_ref().and_then(|p| p.index_of(name));
+        for predicate in &mut opt.predicates {
+            predicate.resolve_names(&index_of).map_err(|name| {
+                let help = match &preset {
+                    Some(preset) => preset.names(),
+                    None => String::from("field names need a --preset that names the fields"),
+                };
+                Error::Config(format!("unknown field {} in --where; {}", name, help))
+            })?;
+        }
+
+        let excluding = opt.choices.iter().any(|c| c.excluded);
+
+        let separator = match opt.field_separator.as_slice() {
+            [] => match (&opt.separators, &opt.whitespace_chars) {
+                (Some(chars), _) => {
+                    let chars = chars.chars().collect::<Vec<_>>();
+                    match chars.as_slice() {
+                        [c] => Separator::LiteralChar(*c),
+                        chars => Separator::LiteralChars(CharSet::new(chars)),
+                    }
                 }
-            }
-            None => Separator::Whitespace,
+                (None, Some(chars)) => Separator::WhitespaceChars(chars.chars().collect()),
+                (None, None) if opt.ascii_whitespace => Separator::AsciiWhitespace,
+                (None, None) => match preset.as_ref().and_then(|p| p.pattern_for(&opt)) {
+                    Some(pattern) => Separator::Pattern(Regex::new(pattern)?),
+                    None => Separator::Whitespace,
+                },
+            },
+            patterns => Separator::any(patterns)?,
+        };
+
+        let literal_option = match (opt.escape, opt.quote) {
+            (Some(_), _) => Some("--escape"),
+            (None, Some(_)) => Some("--quote"),
+            (None, None) => None,
         };
+        if let Some(option) = literal_option {
+            match separator {
+                Separator::LiteralChar(_) | Separator::LiteralStr(_) => (),
+                _ => return Err(Error::Config(format!("{} requires a literal field separator", option))),
+            }
+        }
 
         let output_separator = match opt.character_wise {
             false => match opt.output_field_separator.clone() {
@@ -83,12 +225,115 @@ impl Config {
             },
         };
 
-        Config {
+        let empty_fields = match opt.empty_fields {
+            Some(empty_fields) => empty_fields,
+            None if opt.non_greedy => EmptyFields::Keep,
+            None => EmptyFields::Drop,
+        };
+
+        Ok(Config {
             opt,
             separator,
             output_separator,
+            empty_fields,
+            excluding,
+            template,
+        })
+    }
+}
+
+impl Config {
+    /// All of the choices to print, whether given as arguments or in a
+    /// `--format` template
+    pub fn choices(&self) -> impl Iterator<Item = &Choice> {
+        let template = self.template.iter().flat_map(|t| t.choices());
+        self.opt.choices.iter().chain(template)
+    }
+
+    /// Whether a line satisfies every `--where` predicate, and so should be
+    /// printed
+    pub fn matches(&self, line: &str) -> bool {
+        if self.opt.predicates.is_empty() {
+            return true;
+        }
+
+        let fields = self.fields(line);
+        self.opt.predicates.iter().all(|p| p.matches(&fields))
+    }
+
+    /// Split a line into all of its fields according to the configured
+    /// separator, escape or quote character, and character-wise mode
+    ///
+    /// This is slower than iterating over the fields lazily, so it is reserved
+    /// for choices that need random access to the fields of a line.
+    pub fn fields<'a>(&self, line: &'a str) -> Vec<Cow<'a, str>> {
+        if self.opt.character_wise {
+            return line
+                .char_indices()
+                .map(|(i, c)| Cow::Borrowed(&line[i..i + c.len_utf8()]))
+                .collect();
+        }
+
+        let mut buf = [0u8; 4];
+        let literal_separator = match &self.separator {
+            Separator::LiteralChar(c) => Some(&*c.encode_utf8(&mut buf)),
+            Separator::LiteralStr(s) => Some(s.as_str()),
+            _ => None,
+        };
+
+        match (literal_separator, self.opt.escape, self.opt.quote) {
+            (Some(separator), Some(escape), _) => self
+                .empty_fields
+                .filter(EscapedSplit::new(line, separator, escape, self.opt.unescape))
+                .collect(),
+            (Some(separator), None, Some(quote)) => self
+                .empty_fields
+                .filter(QuotedSplit::new(line, separator, quote))
+                .map(Cow::Borrowed)
+                .collect(),
+            _ => self
+                .separator
+                .split(line, self.empty_fields)
+                .into_iter()
+                .map(Cow::Borrowed)
+                .collect(),
+        }
+    }
+}
+
+/// adjust_choice converts the indexes of a choice as written on the command
+/// line into zero-indexed, inclusive indexes, including any nested choices and
+/// subscripts
+fn adjust_choice(choice: &mut Choice, exclusive: bool, one_indexed: bool) {
+    if (exclusive && choice.kind == ChoiceKind::ColonRange)
+        || choice.kind == ChoiceKind::RustExclusiveRange
+    {
+        if choice.is_reverse_range() {
+            choice.start -= 1;
+        } else {
+            choice.end -= 1;
+        }
+    }
+
+    // the indexes of an anchored choice are offsets from the anchor field, so
+    // they are the same whether or not fields are one-indexed
+    if one_indexed && choice.anchor.is_none() {
+        if choice.start > 0 {
+            choice.start -= 1;
+        }
+
+        if choice.end > 0 {
+            choice.end -= 1;
         }
     }
+
+    if let Some(nested) = &mut choice.nested {
+        adjust_choice(&mut nested.choice, exclusive, one_indexed);
+    }
+
+    if let Some(subscript) = &mut choice.subscript {
+        adjust_choice(subscript, exclusive, one_indexed);
+    }
 }
 
 /// regex_is_literal_char determines if a given regex pattern would match only
@@ -137,6 +382,16 @@ fn regex_is_literal_char(s: &str) -> Option<char> {
     }
 }
 
+/// regex_is_literal_str determines if a given regex pattern contains no
+/// characters with special regex significance, in which case it matches only
+/// literal instances of itself (which may be a single char)
+///
+/// Like regex_is_literal_char, false negatives are acceptable but false
+/// positives are not.
+fn regex_is_literal_str(s: &str) -> bool {
+    !s.is_empty() && regex::escape(s) == s
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -217,4 +472,46 @@ mod test {
         test_regex_is_literal_char_none("{");
         test_regex_is_literal_char_none("}");
     }
+
+    fn separator_any(patterns: &[&str]) -> Separator {
+        Separator::any(&patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn test_separator_any_single() {
+        assert!(matches!(separator_any(&[","]), Separator::LiteralChar(',')));
+        assert!(matches!(separator_any(&["::"]), Separator::LiteralStr(_)));
+        assert!(matches!(separator_any(&["[,;]"]), Separator::Regex(_)));
+    }
+
+    #[test]
+    fn test_separator_any_literal_chars() {
+        assert!(matches!(separator_any(&[",", ";", "\\|"]), Separator::LiteralChars(_)));
+    }
+
+    #[test]
+    fn test_separator_any_regex() {
+        assert!(matches!(separator_any(&[",", "::"]), Separator::Regex(_)));
+        assert!(matches!(separator_any(&[",", "a+"]), Separator::Regex(_)));
+    }
+
+    #[test]
+    fn test_separator_any_invalid() {
+        assert!(Separator::any(&[",".to_string(), "[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_regex_is_literal_str() {
+        assert!(regex_is_literal_str("::"));
+        assert!(regex_is_literal_str(", "));
+        assert!(regex_is_literal_str("abc"));
+
+        assert!(regex_is_literal_str("="));
+
+        assert!(!regex_is_literal_str(""));
+        assert!(!regex_is_literal_str("a|b"));
+        assert!(!regex_is_literal_str("a."));
+        assert!(!regex_is_literal_str("\\t"));
+        assert!(!regex_is_literal_str("[aeiou]"));
+    }
 }
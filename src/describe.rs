@@ -0,0 +1,338 @@
+use regex::Regex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::BufRead;
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::result::Result;
+
+lazy_static! {
+    static ref TIMESTAMP_RE: Regex = Regex::new(concat!(
+        // ISO 8601 dates and date-times, e.g. 2024-01-15 or 2024-01-15T10:00:00Z
+        r"^(?:\d{4}-\d{2}-\d{2}(?:[T ]\d{2}:\d{2}(?::\d{2}(?:[.,]\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?)?",
+        // times, e.g. 10:00:00 or 10:00:00.123
+        r"|\d{2}:\d{2}:\d{2}(?:[.,]\d+)?",
+        // common log format, e.g. [15/Jan/2024:10:00:00 +0000]
+        r"|\[?\d{2}/[A-Z][a-z]{2}/\d{4}:\d{2}:\d{2}:\d{2}(?: [+-]\d{4})?\]?",
+        r")$"
+    ))
+    .unwrap();
+}
+
+/// The number of sample values kept for each field
+const SAMPLES: usize = 3;
+
+/// The number of hashes kept by each cardinality sketch
+const SKETCH_SIZE: usize = 256;
+
+/// Description is the shape of an input, inferred with `--describe`: how many
+/// fields its lines have and what each field looks like
+#[derive(Debug, Default)]
+pub struct Description {
+    pub lines: usize,
+    pub field_counts: BTreeMap<usize, usize>,
+    /// Fields by index from the start of a line
+    pub fields: Vec<FieldDescription>,
+    /// Fields by index from the end of a line, starting with -1
+    pub fields_from_end: Vec<FieldDescription>,
+}
+
+/// FieldDescription is what the values of one field index look like across
+/// the lines of an input
+#[derive(Debug, Default)]
+pub struct FieldDescription {
+    pub present: usize,
+    pub ints: usize,
+    pub floats: usize,
+    pub timestamps: usize,
+    pub samples: Vec<String>,
+    sketch: BTreeSet<u64>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FieldType {
+    Int,
+    Float,
+    Timestamp,
+    Text,
+}
+
+impl Description {
+    /// Read every line of an input and describe it, skipping lines that do
+    /// not match any `--where` predicates
+    pub fn read<R: BufRead>(reader: &mut R, config: &Config) -> Result<Self> {
+        let mut description = Description::default();
+        let mut buffer = String::new();
+
+        loop {
+            buffer.clear();
+            if reader.read_line(&mut buffer).map_err(Error::Read)? == 0 {
+                break;
+            }
+
+            let line = buffer.trim_end_matches('\n').trim_end_matches('\r');
+            if config.matches(line) {
+                description.add_line(&config.fields(line));
+            }
+        }
+
+        Ok(description)
+    }
+
+    pub fn add_line<S: AsRef<str>>(&mut self, fields: &[S]) {
+        self.lines += 1;
+        *self.field_counts.entry(fields.len()).or_default() += 1;
+
+        if self.fields.len() < fields.len() {
+            self.fields.resize_with(fields.len(), FieldDescription::default);
+            self.fields_from_end.resize_with(fields.len(), FieldDescription::default);
+        }
+
+        for (i, field) in fields.iter().enumerate() {
+            self.fields[i].add(field.as_ref());
+        }
+        for (i, field) in fields.iter().rev().enumerate() {
+            self.fields_from_end[i].add(field.as_ref());
+        }
+    }
+}
+
+impl FieldDescription {
+    fn add(&mut self, value: &str) {
+        self.present += 1;
+
+        match infer_type(value) {
+            FieldType::Int => self.ints += 1,
+            FieldType::Float => self.floats += 1,
+            FieldType::Timestamp => self.timestamps += 1,
+            FieldType::Text => (),
+        }
+
+        if self.samples.len() < SAMPLES && !self.samples.iter().any(|s| s == value) {
+            self.samples.push(value.to_string());
+        }
+
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        self.sketch.insert(hasher.finish());
+        if self.sketch.len() > SKETCH_SIZE {
+            self.sketch.pop_last();
+        }
+    }
+
+    /// The type every value of the field has, widening ints to floats if the
+    /// field has both
+    pub fn field_type(&self) -> FieldType {
+        if self.ints == self.present {
+            FieldType::Int
+        } else if self.ints + self.floats == self.present {
+            FieldType::Float
+        } else if self.timestamps == self.present {
+            FieldType::Timestamp
+        } else {
+            FieldType::Text
+        }
+    }
+
+    /// Estimate the number of distinct values of the field
+    ///
+    /// This is exact for up to SKETCH_SIZE distinct values. Beyond that, it is
+    /// estimated from how densely the smallest SKETCH_SIZE hashes of the values
+    /// are spread over the range of hashes (a k-minimum values sketch).
+    pub fn cardinality(&self) -> usize {
+        match self.sketch.last() {
+            Some(max) if self.sketch.len() == SKETCH_SIZE => {
+                ((SKETCH_SIZE - 1) as f64 * (u64::MAX as f64 / *max as f64)) as usize
+            }
+            _ => self.sketch.len(),
+        }
+    }
+
+    fn is_estimate(&self) -> bool {
+        self.sketch.len() == SKETCH_SIZE
+    }
+}
+
+/// Infer the type of a single value
+pub fn infer_type(value: &str) -> FieldType {
+    if value.parse::<i64>().is_ok() {
+        FieldType::Int
+    } else if value.contains(|c: char| c.is_ascii_digit())
+        && value.chars().all(|c| c.is_ascii_digit() || "+-.eE".contains(c))
+        && value.parse::<f64>().is_ok()
+    {
+        FieldType::Float
+    } else if TIMESTAMP_RE.is_match(value) {
+        FieldType::Timestamp
+    } else {
+        FieldType::Text
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldType::Int => write!(f, "int"),
+            FieldType::Float => write!(f, "float"),
+            FieldType::Timestamp => write!(f, "timestamp"),
+            FieldType::Text => write!(f, "text"),
+        }
+    }
+}
+
+impl fmt::Display for Description {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "lines: {}", self.lines)?;
+
+        writeln!(f, "field counts:")?;
+        for (count, lines) in &self.field_counts {
+            writeln!(f, "  {:>5} fields: {} line{}", count, lines, if *lines == 1 { "" } else { "s" })?;
+        }
+
+        writeln!(f, "fields:")?;
+        for (i, field) in self.fields.iter().enumerate() {
+            write_field(f, &i.to_string(), field, self.lines)?;
+        }
+
+        writeln!(f, "fields from end:")?;
+        for (i, field) in self.fields_from_end.iter().enumerate() {
+            write_field(f, &format!("-{}", i + 1), field, self.lines)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_field(f: &mut fmt::Formatter, index: &str, field: &FieldDescription, lines: usize) -> fmt::Result {
+    let samples = field
+        .samples
+        .iter()
+        .map(|s| format!("{:?}", truncate(s, 24)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    writeln!(
+        f,
+        "  {:>5}  {:<9}  {}{:<7} distinct  in {}/{} lines  e.g. {}",
+        index,
+        field.field_type().to_string(),
+        if field.is_estimate() { "~" } else { " " },
+        field.cardinality(),
+        field.present,
+        lines,
+        samples
+    )
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some((i, _)) => format!("{}...", &s[..i]),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+
+    fn describe(vec: Vec<&str>, input: &str) -> Description {
+        let config = Config::from_iter(vec);
+        Description::read(&mut Cursor::new(input), &config).unwrap()
+    }
+
+    #[test]
+    fn infer_types() {
+        assert_eq!(FieldType::Int, infer_type("-42"));
+        assert_eq!(FieldType::Float, infer_type("3.5"));
+        assert_eq!(FieldType::Float, infer_type("1e6"));
+        assert_eq!(FieldType::Text, infer_type("inf"));
+        assert_eq!(FieldType::Timestamp, infer_type("2024-01-15"));
+        assert_eq!(FieldType::Timestamp, infer_type("2024-01-15T10:00:00Z"));
+        assert_eq!(FieldType::Timestamp, infer_type("10:00:00"));
+        assert_eq!(FieldType::Timestamp, infer_type("[15/Jan/2024:10:00:00"));
+        assert_eq!(FieldType::Text, infer_type("GET"));
+    }
+
+    #[test]
+    fn field_count_histogram() {
+        let d = describe(vec!["choose", "--describe"], "a b c\nd e f\ng h\n");
+        assert_eq!(3, d.lines);
+        assert_eq!(vec![(2, 1), (3, 2)], d.field_counts.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn field_types() {
+        let d = describe(vec!["choose", "--describe"], "1 2.5 x 2024-01-15\n2 3 y 2024-01-16\n");
+        let types = d.fields.iter().map(FieldDescription::field_type).collect::<Vec<_>>();
+        assert_eq!(vec![FieldType::Int, FieldType::Float, FieldType::Text, FieldType::Timestamp], types);
+    }
+
+    #[test]
+    fn fields_from_end() {
+        let d = describe(vec!["choose", "--describe"], "a b 1\nc 2\n");
+        assert_eq!(FieldType::Int, d.fields_from_end[0].field_type());
+        assert_eq!(FieldType::Text, d.fields[1].field_type());
+        assert_eq!((2, 1), (d.fields_from_end[1].present, d.fields_from_end[2].present));
+    }
+
+    #[test]
+    fn samples_are_distinct() {
+        let d = describe(vec!["choose", "--describe"], "a\na\nb\nc\nd\n");
+        assert_eq!(vec!["a", "b", "c"], d.fields[0].samples);
+    }
+
+    #[test]
+    fn cardinality_exact() {
+        let d = describe(vec!["choose", "--describe"], "a\nb\na\nc\n");
+        assert_eq!(3, d.fields[0].cardinality());
+        assert!(!d.fields[0].is_estimate());
+    }
+
+    #[test]
+    fn cardinality_estimate() {
+        let mut d = Description::default();
+        for i in 0..20000 {
+            d.add_line(&[i.to_string()]);
+        }
+        let estimate = d.fields[0].cardinality();
+        assert!(d.fields[0].is_estimate());
+        assert!((15000..25000).contains(&estimate), "estimate {} is too far off", estimate);
+    }
+
+    #[test]
+    fn describe_field_separator() {
+        let d = describe(vec!["choose", "--describe", "-f", ":"], "root:x:0\n");
+        assert_eq!(3, d.fields.len());
+    }
+
+    #[test]
+    fn describe_where() {
+        let d = describe(vec!["choose", "--describe", "--where", "0==a"], "a 1\nb 2\n");
+        assert_eq!(1, d.lines);
+    }
+
+    #[test]
+    fn display() {
+        let d = describe(vec!["choose", "--describe"], "a 1\nb 2\n");
+        let lines = d.to_string();
+        let lines = lines.lines().collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "lines: 2",
+                "field counts:",
+                "      2 fields: 2 lines",
+                "fields:",
+                "      0  text        2       distinct  in 2/2 lines  e.g. \"a\", \"b\"",
+                "      1  int         2       distinct  in 2/2 lines  e.g. \"1\", \"2\"",
+                "fields from end:",
+                "     -1  int         2       distinct  in 2/2 lines  e.g. \"1\", \"2\"",
+                "     -2  text        2       distinct  in 2/2 lines  e.g. \"a\", \"b\"",
+            ],
+            lines
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+/// EmptyFields is the policy for empty fields, i.e. those between two adjacent
+/// field separators or between a field separator and the start or end of a
+/// line
+///
+/// The policy is applied when a line is split, so an empty field that is kept
+/// is indexed and printed (along with its output separators) just like any
+/// other field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmptyFields {
+    /// Drop all empty fields, like `awk` with a non-whitespace separator
+    Drop,
+    /// Keep all empty fields, like `cut`
+    Keep,
+    /// Keep empty fields between non-empty fields, but drop those at the
+    /// start and end of a line
+    KeepInterior,
+}
+
+impl FromStr for EmptyFields {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drop" => Ok(EmptyFields::Drop),
+            "keep" => Ok(EmptyFields::Keep),
+            "keep-interior" => Ok(EmptyFields::KeepInterior),
+            _ => Err(format!("invalid empty field policy: {}", s)),
+        }
+    }
+}
+
+impl EmptyFields {
+    pub const VARIANTS: &'static [&'static str] = &["drop", "keep", "keep-interior"];
+
+    /// Apply the policy to an iterator of fields
+    pub fn filter<T, I>(self, iter: I) -> Filter<T, I>
+    where
+        T: AsRef<str> + Default,
+        I: Iterator<Item = T>,
+    {
+        Filter {
+            inner: iter,
+            policy: self,
+            started: false,
+            pending_empty: 0,
+            pending: None,
+        }
+    }
+}
+
+/// Filter is an iterator adapter that applies an EmptyFields policy
+///
+/// For KeepInterior, runs of empty fields are counted rather than yielded
+/// until a non-empty field follows them, so trailing empty fields are never
+/// yielded.
+pub struct Filter<T, I> {
+    inner: I,
+    policy: EmptyFields,
+    started: bool,
+    pending_empty: usize,
+    pending: Option<T>,
+}
+
+impl<T, I> Iterator for Filter<T, I>
+where
+    T: AsRef<str> + Default,
+    I: Iterator<Item = T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self.policy {
+            EmptyFields::Keep => self.inner.next(),
+            EmptyFields::Drop => self.inner.find(|s| !s.as_ref().is_empty()),
+            EmptyFields::KeepInterior => {
+                if self.pending_empty > 0 {
+                    self.pending_empty -= 1;
+                    return Some(T::default());
+                }
+
+                if let Some(s) = self.pending.take() {
+                    return Some(s);
+                }
+
+                let mut empty = 0;
+                for s in self.inner.by_ref() {
+                    if !s.as_ref().is_empty() {
+                        self.started = true;
+                        if empty == 0 {
+                            return Some(s);
+                        }
+                        self.pending = Some(s);
+                        self.pending_empty = empty - 1;
+                        return Some(T::default());
+                    } else if self.started {
+                        empty += 1;
+                    }
+                }
+
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn filter(policy: EmptyFields, line: &str) -> Vec<&str> {
+        policy.filter(line.split(',')).collect()
+    }
+
+    #[test]
+    fn filter_drop() {
+        assert_eq!(vec!["a", "b"], filter(EmptyFields::Drop, ",a,,b,"));
+    }
+
+    #[test]
+    fn filter_keep() {
+        assert_eq!(vec!["", "a", "", "b", ""], filter(EmptyFields::Keep, ",a,,b,"));
+    }
+
+    #[test]
+    fn filter_keep_interior() {
+        assert_eq!(vec!["a", "", "b"], filter(EmptyFields::KeepInterior, ",a,,b,"));
+        assert_eq!(vec!["a", "", "", "b"], filter(EmptyFields::KeepInterior, ",,a,,,b,,"));
+        assert_eq!(vec!["a", "b"], filter(EmptyFields::KeepInterior, "a,b"));
+    }
+
+    #[test]
+    fn filter_keep_interior_all_empty() {
+        assert!(filter(EmptyFields::KeepInterior, ",,,").is_empty());
+    }
+
+    #[test]
+    fn parse_policy() {
+        assert_eq!(EmptyFields::Drop, "drop".parse().unwrap());
+        assert_eq!(EmptyFields::Keep, "keep".parse().unwrap());
+        assert_eq!(EmptyFields::KeepInterior, "keep-interior".parse().unwrap());
+        assert!("interior".parse::<EmptyFields>().is_err());
+    }
+}
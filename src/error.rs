@@ -1,63 +1,141 @@
 use std::error::Error as StdError;
 use std::fmt;
+use std::io;
 use std::num::TryFromIntError;
+use std::path::PathBuf;
 
+use crate::parse_error::ParseError;
+
+/// Error is every way choose can fail, each of which exits with its own exit
+/// code:
+///
+/// | code | meaning                                                        |
+/// |------|----------------------------------------------------------------|
+/// | 0    | success, or the reader of the output hung up                   |
+/// | 1    | invalid arguments, such as a choice or an index of isize::MIN  |
+/// | 2    | invalid configuration, such as a field separator regex         |
+/// | 3    | failed to open the input file                                  |
+/// | 4    | a choice could not be satisfied in `--strict` mode             |
+/// | 5    | failed to read the input                                       |
+/// | 6    | failed to write the output                                     |
+/// | 7    | an index too large to convert to a usable integer              |
 #[derive(Debug)]
 pub enum Error {
-    Io(std::io::Error),
-    ParseRange(ParseRangeError),
+    /// Failed to write the output
+    Io(io::Error),
+    /// An index that overflowed converting between signed and unsigned
+    /// integers, e.g. one beyond isize::MAX
     TryFromInt(TryFromIntError),
+    /// An argument that could not be parsed, and the name of the argument
+    Parse(&'static str, ParseError),
+    /// An index that is a valid number but cannot be used, i.e. isize::MIN,
+    /// which has no positive counterpart to count from the end of a line with
+    Index(String),
+    /// A field separator regex that could not be compiled
+    Regex(regex::Error),
+    /// Any other invalid configuration, e.g. an unknown preset or a bad key in
+    /// the config file
     Config(String),
+    /// The input file that could not be opened, and why
+    Open(PathBuf, io::Error),
+    /// Failed to read the input
+    Read(io::Error),
+    /// A choice that a line could not satisfy with `--strict`, as the message
+    /// reporting it
+    Strict(String),
+}
+
+impl Error {
+    /// The exit code of the process when it fails with this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Parse(..) | Self::Index(_) => 1,
+            Self::Regex(_) | Self::Config(_) => 2,
+            Self::Open(..) => 3,
+            Self::Strict(_) => 4,
+            Self::Read(_) => 5,
+            Self::Io(_) => 6,
+            Self::TryFromInt(_) => 7,
+        }
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Io(io) => write!(f, "{}", io),
-            Self::ParseRange(pr) => write!(f, "{}", pr),
-            Self::TryFromInt(tfi) => write!(f, "{}", tfi),
-            Self::Config(c) => write!(f, "{}", c),
+            Self::Io(io) => write!(f, "failed to write to output: {}", io),
+            Self::TryFromInt(tfi) => write!(f, "index out of range: {}", tfi),
+            Self::Parse(arg, e) => write!(f, "Invalid value for '{}': {}", arg, e),
+            Self::Regex(regex::Error::Syntax(e)) => {
+                write!(f, "syntax error compiling regular expression: {}", e)
+            }
+            Self::Regex(regex::Error::CompiledTooBig(size)) => write!(
+                f,
+                "compiled regular expression too big: compiled size cannot exceed {} bytes",
+                size
+            ),
+            Self::Regex(e) => write!(f, "error compiling regular expression: {}", e),
+            Self::Index(i) | Self::Config(i) => write!(f, "{}", i),
+            Self::Open(path, e) => write!(f, "failed to open file {}: {}", path.display(), e),
+            Self::Read(e) => write!(f, "failed to read input: {}", e),
+            Self::Strict(s) => write!(f, "{}", s),
         }
     }
 }
 
 impl StdError for Error {}
 
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
         Self::Io(e)
     }
 }
 
-impl From<ParseRangeError> for Error {
-    fn from(e: ParseRangeError) -> Self {
-        Self::ParseRange(e)
-    }
-}
-
 impl From<TryFromIntError> for Error {
     fn from(e: TryFromIntError) -> Self {
         Self::TryFromInt(e)
     }
 }
 
-#[derive(Debug)]
-pub struct ParseRangeError {
-    source_str: String,
+impl From<regex::Error> for Error {
+    fn from(e: regex::Error) -> Self {
+        Self::Regex(e)
+    }
 }
 
-impl ParseRangeError {
-    pub fn new(source_str: &str) -> Self {
-        ParseRangeError {
-            source_str: String::from(source_str),
-        }
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse_error::ParseErrorKind;
+
+    #[test]
+    fn exit_codes() {
+        let errors = [
+            Error::Parse("<choices>...", ParseError::whole(ParseErrorKind::Empty, "")),
+            Error::Index(String::new()),
+            Error::Regex(regex::Error::Syntax(String::new())),
+            Error::Config(String::new()),
+            Error::Open(PathBuf::new(), io::ErrorKind::NotFound.into()),
+            Error::Strict(String::new()),
+            Error::Read(io::ErrorKind::InvalidData.into()),
+            Error::Io(io::ErrorKind::Other.into()),
+            Error::TryFromInt(u8::try_from(-1i32).unwrap_err()),
+        ];
+        let codes = errors.iter().map(Error::exit_code).collect::<Vec<_>>();
+        assert_eq!(vec![1, 1, 2, 2, 3, 4, 5, 6, 7], codes);
     }
-}
 
-impl fmt::Display for ParseRangeError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.source_str)
+    #[test]
+    fn display_parse() {
+        let e = Error::Parse("<choices>...", ParseError::whole(ParseErrorKind::Invalid, "x"));
+        assert!(e.to_string().starts_with("Invalid value for '<choices>...': not a valid choice\n"));
     }
-}
 
-impl StdError for ParseRangeError {}
+    #[test]
+    fn display_regex() {
+        let pattern = String::from("a{99999999}");
+        let e = Error::from(regex::Regex::new(&pattern).unwrap_err());
+        assert_eq!(2, e.exit_code());
+        assert!(e.to_string().contains("regular expression"));
+    }
+}
@@ -0,0 +1,128 @@
+use std::borrow::Cow;
+
+/// EscapedSplit splits a line on a literal separator, except where the
+/// separator is preceded by the escape character
+///
+/// The escape character escapes whatever character follows it, so an escaped
+/// escape character (e.g. `\\` when the escape is `\`) does not prevent the
+/// separator after it from splitting the line.
+pub struct EscapedSplit<'a, 'b> {
+    remainder: Option<&'a str>,
+    separator: &'b str,
+    escape: char,
+    unescape: bool,
+}
+
+impl<'a, 'b> EscapedSplit<'a, 'b> {
+    pub fn new(line: &'a str, separator: &'b str, escape: char, unescape: bool) -> Self {
+        EscapedSplit {
+            remainder: Some(line),
+            separator,
+            escape,
+            unescape,
+        }
+    }
+
+    fn finish_field(&self, field: &'a str) -> Cow<'a, str> {
+        if self.unescape {
+            unescape(field, self.escape)
+        } else {
+            Cow::Borrowed(field)
+        }
+    }
+}
+
+impl<'a> Iterator for EscapedSplit<'a, '_> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remainder = self.remainder?;
+
+        let mut escaped = false;
+        for (i, c) in remainder.char_indices() {
+            if escaped {
+                escaped = false;
+            } else if c == self.escape {
+                escaped = true;
+            } else if remainder[i..].starts_with(self.separator) {
+                self.remainder = Some(&remainder[i + self.separator.len()..]);
+                return Some(self.finish_field(&remainder[..i]));
+            }
+        }
+
+        self.remainder = None;
+        Some(self.finish_field(remainder))
+    }
+}
+
+/// unescape removes each escape character from a field, keeping the character
+/// it escapes
+///
+/// A trailing escape character with nothing to escape is kept as-is.
+fn unescape(field: &str, escape: char) -> Cow<'_, str> {
+    if !field.contains(escape) {
+        return Cow::Borrowed(field);
+    }
+
+    let mut s = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c == escape {
+            match chars.next() {
+                Some(escaped) => s.push(escaped),
+                None => s.push(c),
+            }
+        } else {
+            s.push(c);
+        }
+    }
+    Cow::Owned(s)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn split(line: &str, separator: &str, unescape: bool) -> Vec<String> {
+        EscapedSplit::new(line, separator, '\\', unescape)
+            .map(|s| s.into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn split_without_escapes() {
+        assert_eq!(vec!["a", "b", "c"], split("a|b|c", "|", false));
+    }
+
+    #[test]
+    fn split_escaped_separator() {
+        assert_eq!(vec!["a\\|b", "c"], split("a\\|b|c", "|", false));
+    }
+
+    #[test]
+    fn split_escaped_separator_unescaped() {
+        assert_eq!(vec!["a|b", "c"], split("a\\|b|c", "|", true));
+    }
+
+    #[test]
+    fn split_escaped_escape() {
+        assert_eq!(vec!["a\\\\", "b"], split("a\\\\|b", "|", false));
+        assert_eq!(vec!["a\\", "b"], split("a\\\\|b", "|", true));
+    }
+
+    #[test]
+    fn split_escaped_string_separator() {
+        assert_eq!(vec!["a\\::b", "c"], split("a\\::b::c", "::", false));
+        assert_eq!(vec!["a::b", "c"], split("a\\::b::c", "::", true));
+    }
+
+    #[test]
+    fn split_trailing_escape() {
+        assert_eq!(vec!["a", "b\\"], split("a|b\\", "|", true));
+    }
+
+    #[test]
+    fn split_empty_fields() {
+        assert_eq!(vec!["", "a", "", ""], split("|a||", "|", false));
+    }
+}
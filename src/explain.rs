@@ -0,0 +1,176 @@
+use std::io::{self, Write};
+
+use crate::choice::Choice;
+use crate::config::{Config, Separator};
+use crate::result::Result;
+
+/// Explain on `out` how a line is split into fields and which fields each
+/// choice resolves to, for `--debug`
+pub fn print_line<W: Write>(out: &mut W, config: &Config, line: &str, line_number: usize) -> Result<()> {
+    writeln!(out, "line {}: {:?}", line_number, line)?;
+    writeln!(out, "  separator: {}", describe_separator(config))?;
+
+    let fields = config.fields(line);
+    writeln!(out, "  fields ({}):", fields.len())?;
+    for (i, field) in fields.iter().enumerate() {
+        writeln!(out, "    [{}] {:?}", i, field)?;
+    }
+
+    for choice in config.choices() {
+        writeln!(out, "  choice {}: {}", choice.source, describe_choice(choice, &fields)?)?;
+    }
+
+    if !config.opt.predicates.is_empty() && !config.matches(line) {
+        writeln!(out, "  skipped: does not match --where")?;
+    }
+
+    Ok(())
+}
+
+/// Explain on stderr how a line is split
+pub fn eprint_line(config: &Config, line: &str, line_number: usize) -> Result<()> {
+    let stderr = io::stderr();
+    print_line(&mut stderr.lock(), config, line, line_number)
+}
+
+fn describe_separator(config: &Config) -> String {
+    if config.opt.character_wise {
+        return String::from("none, each character is a field (--character-wise)");
+    }
+
+    let patterns = &config.opt.field_separator;
+
+    let mut description = match &config.separator {
+        Separator::Whitespace => String::from("Whitespace, runs of Unicode whitespace"),
+        Separator::AsciiWhitespace => String::from("AsciiWhitespace, runs of ASCII whitespace"),
+        Separator::WhitespaceChars(chars) => format!("WhitespaceChars({:?}), runs of any of these", chars),
+        Separator::LiteralChar(c) => format!("LiteralChar({:?})", c),
+        Separator::LiteralStr(s) => format!("LiteralStr({:?})", s),
+        Separator::LiteralChars(_) => match &config.opt.separators {
+            Some(chars) => format!("LiteralChars({:?}), any one of these", chars),
+            None => String::from("LiteralChars, any one of several chars"),
+        },
+        Separator::Regex(r) => format!("Regex({:?})", r.as_str()),
+        Separator::Pattern(r) => format!("Pattern({:?}), the capture groups of the regex", r.as_str()),
+    };
+
+    match &config.separator {
+        Separator::LiteralChar(_) | Separator::LiteralStr(_) | Separator::LiteralChars(_)
+            if !patterns.is_empty() =>
+        {
+            let patterns = patterns.iter().map(|p| format!("{:?}", p)).collect::<Vec<_>>();
+            let regex = if patterns.len() == 1 { "regex" } else { "regexes" };
+            description.push_str(&format!(", optimized from the literal-only {} {}", regex, patterns.join(", ")));
+        }
+        _ => (),
+    }
+
+    if let Some(escape) = config.opt.escape {
+        description.push_str(&format!(", escaped with {:?}", escape));
+    }
+
+    if let Some(quote) = config.opt.quote {
+        description.push_str(&format!(", quoted with {:?}", quote));
+    }
+
+    // the whitespace separators never produce empty fields
+    if let Separator::LiteralChar(_) | Separator::LiteralStr(_) | Separator::LiteralChars(_) | Separator::Regex(_) =
+        config.separator
+    {
+        description.push_str(&format!(", {:?} empty fields", config.empty_fields));
+    }
+    description
+}
+
+fn describe_choice<S: AsRef<str>>(choice: &Choice, fields: &[S]) -> Result<String> {
+    let end = match choice.end {
+        isize::MAX => String::from("end of line"),
+        end => end.to_string(),
+    };
+    let mut description = format!("start {}, end {}", choice.start, end);
+
+    if choice.anchor.is_some() {
+        description.push_str(" relative to the anchor field");
+    }
+
+    if choice.step != 1 {
+        description.push_str(&format!(", step {}", choice.step));
+    }
+
+    let indices = choice.resolve(fields)?;
+    match (indices.first(), indices.last()) {
+        (Some(first), Some(last)) if first == last => description.push_str(&format!(" -> field {}", first)),
+        (Some(first), Some(last)) => {
+            description.push_str(&format!(" -> fields {} to {} ({} fields)", first, last, indices.len()))
+        }
+        _ => description.push_str(" -> no fields"),
+    }
+
+    if choice.nested.is_some() {
+        description.push_str(", each split again by a nested choice");
+    }
+
+    if choice.subscript.is_some() {
+        description.push_str(", each with a subscript");
+    }
+
+    if choice.excluded {
+        description.push_str(", excluded");
+    }
+
+    Ok(description)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn explain(vec: Vec<&str>, line: &str) -> String {
+        let config = Config::from_iter(vec);
+        let mut out = Vec::new();
+        print_line(&mut out, &config, line, 1).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn explain_fields_and_choices() {
+        assert_eq!(
+            "line 1: \"a b c\"\n  \
+             separator: Whitespace, runs of Unicode whitespace\n  \
+             fields (3):\n    [0] \"a\"\n    [1] \"b\"\n    [2] \"c\"\n  \
+             choice 0: start 0, end 0 -> field 0\n  \
+             choice -2:: start -2, end end of line -> fields 1 to 2 (2 fields)\n",
+            explain(vec!["choose", "0", "-2:"], "a b c")
+        );
+    }
+
+    #[test]
+    fn explain_literal_separator() {
+        let out = explain(vec!["choose", "-f", ":", "0"], "a:b");
+        assert!(out.contains("separator: LiteralChar(':'), optimized from the literal-only regex \":\", Drop empty fields"));
+    }
+
+    #[test]
+    fn explain_regex_separator() {
+        let out = explain(vec!["choose", "-f", "a+", "0"], "x");
+        assert!(out.contains("separator: Regex(\"a+\"), Drop empty fields\n"));
+    }
+
+    #[test]
+    fn explain_adjusted_choice() {
+        let out = explain(vec!["choose", "-x", "--one-indexed", "1:3"], "a b c d");
+        assert!(out.contains("choice 1:3: start 0, end 1 -> fields 0 to 1 (2 fields)"));
+    }
+
+    #[test]
+    fn explain_missing_fields() {
+        let out = explain(vec!["choose", "5"], "a b");
+        assert!(out.contains("choice 5: start 5, end 5 -> no fields"));
+    }
+
+    #[test]
+    fn explain_where() {
+        let out = explain(vec!["choose", "--where", "0==x", "0"], "a b");
+        assert!(out.ends_with("  skipped: does not match --where\n"));
+    }
+}
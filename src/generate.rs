@@ -0,0 +1,205 @@
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use structopt::StructOpt;
+use structopt::clap::{AppSettings, Shell};
+
+use crate::opt::Opt;
+
+/// The readme, whose examples are included in the man page
+const README: &str = include_str!("../readme.md");
+
+/// Generated is something `--generate` can print from the definition of `Opt`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Generated {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+    /// A roff man page
+    Man,
+}
+
+impl FromStr for Generated {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Generated::Bash),
+            "zsh" => Ok(Generated::Zsh),
+            "fish" => Ok(Generated::Fish),
+            "powershell" => Ok(Generated::PowerShell),
+            "man" => Ok(Generated::Man),
+            _ => Err(format!("invalid generated output: {}", s)),
+        }
+    }
+}
+
+impl Generated {
+    pub const VARIANTS: &'static [&'static str] = &["bash", "zsh", "fish", "powershell", "man"];
+
+    /// Print the completion script or man page
+    pub fn write<W: Write>(self, out: &mut W) -> io::Result<()> {
+        let shell = match self {
+            Generated::Bash => Shell::Bash,
+            Generated::Zsh => Shell::Zsh,
+            Generated::Fish => Shell::Fish,
+            Generated::PowerShell => Shell::PowerShell,
+            Generated::Man => return write_man(out),
+        };
+        Opt::clap().gen_completions_to("choose", shell, out);
+        Ok(())
+    }
+}
+
+/// Print a man page made of the long help of `Opt`, which is parsed back into its sections, and
+/// the examples from the readme
+fn write_man<W: Write>(out: &mut W) -> io::Result<()> {
+    let mut help = Vec::new();
+    Opt::clap()
+        .setting(AppSettings::NextLineHelp)
+        .set_term_width(0)
+        .write_long_help(&mut help)
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let help = String::from_utf8_lossy(&help);
+
+    writeln!(out, ".TH CHOOSE 1 \"\" \"choose {}\"", env!("CARGO_PKG_VERSION"))?;
+    writeln!(out, ".SH NAME")?;
+    writeln!(out, "choose \\- {}", escape(env!("CARGO_PKG_DESCRIPTION")))?;
+
+    for line in help.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let line = line.trim();
+        match indent {
+            0 if line.ends_with(':') => match line.trim_end_matches(':') {
+                "USAGE" => writeln!(out, ".SH SYNOPSIS")?,
+                section => writeln!(out, ".SH {}", section)?,
+            },
+            // the name, version and about that start the help
+            0 => {}
+            _ if line.starts_with("choose ") => writeln!(out, "{}", escape(line))?,
+            // arguments are indented less than their help
+            1..=11 => writeln!(out, ".TP\n\\fB{}\\fR", escape(line))?,
+            _ => writeln!(out, "{}", escape(line))?,
+        }
+    }
+
+    writeln!(out, ".SH EXAMPLES")?;
+    for (command, comment) in examples() {
+        writeln!(out, ".TP\n\\fB{}\\fR\n{}", escape(&command), escape(&comment))?;
+    }
+    Ok(())
+}
+
+/// The commands in the examples of the readme, each with its comment
+fn examples() -> Vec<(String, String)> {
+    let start = README.find("### Examples").expect("readme has examples");
+    let block = README[start..].split("```").nth(1).unwrap_or("");
+
+    let mut examples: Vec<(String, String)> = Vec::new();
+    for line in block.lines().skip(1) {
+        let (command, comment) = match line.find("# ") {
+            Some(i) => (line[..i].trim(), line[i + 2..].trim()),
+            None => (line.trim(), ""),
+        };
+        if !command.is_empty() {
+            examples.push((command.to_string(), comment.to_string()));
+        } else if let Some((_, text)) = examples.last_mut()
+            && !comment.is_empty()
+        {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(comment);
+        }
+    }
+    examples
+}
+
+/// Escape text for roff, so that backslashes and hyphens are printed as they are and a line is
+/// never read as a request
+fn escape(text: &str) -> String {
+    let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+    if escaped.starts_with('.') || escaped.starts_with('\'') {
+        format!("\\&{}", escaped)
+    } else {
+        escaped
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn generate(generated: Generated) -> String {
+        let mut out = Vec::new();
+        generated.write(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn parse_variants() {
+        for variant in Generated::VARIANTS {
+            assert!(variant.parse::<Generated>().is_ok());
+        }
+        assert!("tcsh".parse::<Generated>().is_err());
+    }
+
+    #[test]
+    fn completions_include_options() {
+        for shell in [Generated::Bash, Generated::Zsh, Generated::Fish, Generated::PowerShell] {
+            let completions = generate(shell);
+            assert!(completions.contains("field-separator"));
+            assert!(completions.contains("one-indexed"));
+        }
+    }
+
+    #[test]
+    fn man_sections() {
+        let man = generate(Generated::Man);
+        assert!(man.starts_with(".TH CHOOSE 1 "));
+        for section in ["NAME", "SYNOPSIS", "FLAGS", "OPTIONS", "ARGS", "EXAMPLES"] {
+            assert!(man.contains(&format!("\n.SH {}\n", section)), "missing {}", section);
+        }
+    }
+
+    #[test]
+    fn man_options() {
+        let man = generate(Generated::Man);
+        assert!(man.contains(".TP\n\\fB\\-f, \\-\\-field\\-separator <field\\-separator>...\\fR\n"));
+        assert!(man.contains("\nIndex from 1 instead of 0\n"));
+    }
+
+    #[test]
+    fn man_hides_generate() {
+        assert!(!generate(Generated::Man).contains("\\-\\-generate"));
+    }
+
+    #[test]
+    fn man_examples() {
+        let man = generate(Generated::Man);
+        assert!(man.contains(".TP\n\\fBchoose 5\\fR\nprint the 5th item from a line (zero indexed)\n"));
+        assert!(man.contains(
+            "\\fBchoose \\-f ':' 0 3 5\\fR\nprint the 0th, 3rd, and 5th item from a line, where items are separated by ':' instead of whitespace\n"
+        ));
+    }
+
+    #[test]
+    fn examples_join_comments() {
+        let examples = examples();
+        let (command, comment) = examples.iter().find(|(c, _)| c.starts_with("choose --where")).unwrap();
+        assert_eq!("choose --where '3>500' 1", command);
+        assert_eq!("print the 1st item from lines whose 3rd item is a number greater than 500", comment);
+    }
+
+    #[test]
+    fn escape_roff() {
+        assert_eq!("\\e|", escape("\\|"));
+        assert_eq!("\\-\\-format", escape("--format"));
+        assert_eq!("\\&.TH", escape(".TH"));
+        assert_eq!("\\&'x'", escape("'x'"));
+    }
+}
@@ -1,18 +1,37 @@
+use std::env;
 use std::fs::File;
-use std::io::{self, BufRead, Read};
+use std::io::{self, BufRead, Read, Write};
 use std::process;
+use std::time::Instant;
 use structopt::StructOpt;
 
 #[macro_use]
 extern crate lazy_static;
 
+mod char_set;
 mod choice;
+mod complement;
 mod config;
+mod describe;
+mod empty_fields;
 mod error;
+mod escape;
+mod explain;
+mod generate;
 mod opt;
 mod parse;
 mod parse_error;
+mod predicate;
+mod preset;
+mod quote;
 mod result;
+mod spec_file;
+mod stats;
+mod strict;
+mod template;
+mod unique_fields;
+mod unused;
+mod user_config;
 mod writeable;
 mod writer;
 
@@ -20,82 +39,139 @@ use config::Config;
 use error::Error;
 use opt::Opt;
 use result::Result;
+use stats::{Stats, StatsFormat};
+use strict::Strictness;
+use unique_fields::FieldOrder;
+use unused::UnusedChoices;
+use user_config::UserConfig;
 use writer::WriteReceiver;
 
 use crate::writer::Writer;
 
 fn main() {
-    let opt = Opt::from_args();
+    if let Err(err) = run() {
+        if let Error::Io(e) = &err
+            && e.kind() == io::ErrorKind::BrokenPipe
+        {
+            // BrokenPipe means whoever is reading the output hung up, we should
+            // gracefully exit
+            return;
+        }
+
+        eprintln!("error: {}", err);
+        process::exit(err.exit_code());
+    }
+}
+
+fn run() -> Result<()> {
+    let cli = env::args_os().collect::<Vec<_>>();
+    let user_config = UserConfig::load(&cli)?;
+    let args = user_config.args(&cli)?;
+    let mut opt = Opt::from_iter(args.iter().cloned());
+    opt.presets = user_config.presets.clone();
 
     let stdout = io::stdout();
-    let lock = stdout.lock();
-    let exit_result = match opt.input {
+    let mut lock = stdout.lock();
+
+    if let Some(generated) = opt.generate {
+        return Ok(generated.write(&mut lock)?);
+    }
+
+    if opt.print_config {
+        let args = args.iter().skip(1).map(|arg| arg.to_string_lossy());
+        write!(lock, "{}", user_config)?;
+        writeln!(lock, "arguments:\n    {}", shell_words::join(args))?;
+        return Ok(());
+    }
+
+    match opt.input {
         Some(_) => main_generic(opt, Writer::from(io::BufWriter::new(lock))),
         // it is important to use a LineWriter instead of BufWriter so that if
         // the user is sitting waiting for output for a pipeline that gives lines
         // slowly, they won't have to wait for the buffer to fill up before they
         // see anything (think `tail`ing logs)
         None => main_generic(opt, Writer::from(io::LineWriter::new(lock))),
-    };
-
-    match exit_result {
-        Ok(_) => (),
-        Err(err) => {
-            match err {
-                Error::Io(e) => {
-                    if e.kind() == io::ErrorKind::BrokenPipe {
-                        // BrokenPipe means whoever is reading the output hung up, we should
-                        // gracefully exit
-                    } else {
-                        eprintln!("Failed to write to output: {}", e)
-                    }
-                }
-                e => eprintln!("Error: {}", e),
-            }
-        }
     }
 }
 
 fn main_generic<W: WriteReceiver>(opt: Opt, mut handle: Writer<W>) -> Result<()> {
-    let config = Config::new(opt);
+    let start = Instant::now();
+    let config = Config::new(opt)?;
 
     let read = match &config.opt.input {
-        Some(f) => match File::open(f) {
-            Ok(fh) => Box::new(fh) as Box<dyn Read>,
-            Err(e) => {
-                eprintln!("Failed to open file: {}", e);
-                // exit code of 3 means failure to open input file
-                process::exit(3);
-            }
-        },
+        Some(f) => {
+            let fh = File::open(f).map_err(|e| Error::Open(f.clone(), e))?;
+            Box::new(fh) as Box<dyn Read>
+        }
         None => Box::new(io::stdin()) as Box<dyn Read>,
     };
 
     let mut reader = io::BufReader::new(read);
+
+    if config.opt.describe {
+        let description = describe::Description::read(&mut reader, &config)?;
+        return Ok(handle.write_literal(&description.to_string())?);
+    }
     let mut buffer = String::new();
+    let mut line_number = 0;
+    let mut stats = config.opt.stats.map(|_| Stats::default());
+    let mut unused = config.opt.warn_unused.then(|| UnusedChoices::new(&config));
 
     loop {
         buffer.clear();
-        match reader.read_line(&mut buffer) {
-            Ok(n) => {
-                if n == 0 {
-                    // EOF
-                    break;
-                }
-
-                if buffer.ends_with('\n') {
-                    buffer.pop();
-                    if buffer.ends_with('\r') {
-                        buffer.pop();
-                    }
-                }
-
-                process_all_choices_for_line(&mut handle, &config, &buffer)?;
-
-                handle.write_line()?;
+        let n = reader.read_line(&mut buffer).map_err(Error::Read)?;
+        if n == 0 {
+            // EOF
+            break;
+        }
+
+        if buffer.ends_with('\n') {
+            buffer.pop();
+            if buffer.ends_with('\r') {
+                buffer.pop();
             }
-            Err(e) => println!("Failed to read line: {}", e),
         }
+
+        line_number += 1;
+
+        if config.opt.debug {
+            explain::eprint_line(&config, &buffer, line_number)?;
+        }
+
+        let matches = config.matches(&buffer);
+
+        if let Some(stats) = &mut stats {
+            stats.add_line(&config, &buffer, n, matches)?;
+        }
+
+        if !matches {
+            continue;
+        }
+
+        if let Some(unused) = &mut unused {
+            unused.add_line(&config, &buffer)?;
+        }
+
+        if let Some(strictness) = config.opt.strict {
+            let strictness = strictness.unwrap_or(Strictness::Fail);
+            strict::check_line(&config, &buffer, line_number, strictness)?;
+        }
+
+        process_all_choices_for_line(&mut handle, &config, &buffer)?;
+
+        handle.write_line()?;
+    }
+
+    if let Some(unused) = unused {
+        handle.inner.flush()?;
+        unused.print(&mut io::stderr(), &config)?;
+    }
+
+    if let Some(mut stats) = stats {
+        handle.inner.flush()?;
+        stats.bytes_out = handle.bytes_written();
+        let format = config.opt.stats.flatten().unwrap_or(StatsFormat::Text);
+        stats.print(&mut io::stderr(), format, start.elapsed())?;
     }
 
     Ok(())
@@ -106,6 +182,19 @@ fn process_all_choices_for_line<W: WriteReceiver>(
     config: &Config,
     line: &str,
 ) -> Result<()> {
+    if let Some(template) = &config.template {
+        return template.print_line(handle, config, line);
+    }
+
+    if config.opt.unique_fields && config.opt.choices.iter().any(|c| !c.excluded) {
+        let order = config.opt.unique_order.unwrap_or(FieldOrder::First);
+        return unique_fields::print_line(handle, config, line, order);
+    }
+
+    if config.excluding {
+        return complement::print_line(handle, config, line);
+    }
+
     let _: () = for choice in &config.opt.choices {
         choice.print_choice(line, config, handle)?;
     };
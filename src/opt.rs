@@ -2,34 +2,94 @@ use std::path::PathBuf;
 use structopt::StructOpt;
 
 use crate::choice::Choice;
+use crate::empty_fields::EmptyFields;
+use crate::generate::Generated;
 use crate::parse;
+use crate::predicate::Predicate;
+use crate::preset::Preset;
+use crate::stats::StatsFormat;
+use crate::strict::Strictness;
+use crate::unique_fields::FieldOrder;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "choose", about = "`choose` sections from each line of files")]
 #[structopt(setting = structopt::clap::AppSettings::AllowLeadingHyphen)]
+#[structopt(setting = structopt::clap::AppSettings::AllArgsOverrideSelf)]
 pub struct Opt {
+    /// Split fields on runs of ASCII whitespace only, so that other Unicode whitespace (e.g.
+    /// non-breaking spaces) is part of a field
+    #[structopt(long, conflicts_with_all = &["field-separator", "whitespace-chars"])]
+    pub ascii_whitespace: bool,
+
     /// Choose fields by character number
     #[structopt(short, long)]
     pub character_wise: bool,
 
-    /// Activate debug mode
+    /// Print every field except the chosen ones, in their original order
+    #[structopt(long)]
+    pub complement: bool,
+
+    /// Parse choices like `cut -f`: 1-indexed, with a-b inclusive ranges and -b or a- open ranges
+    #[structopt(long, conflicts_with_all = &["exclusive", "one-indexed"])]
+    pub cut_syntax: bool,
+
+    /// Instead of choosing fields, read all of the input and describe its shape: how many fields
+    /// lines have and, for each field index from the start and end of a line, its inferred type
+    /// (int, float, timestamp, or text), an estimate of its number of distinct values, and samples
+    #[structopt(long, conflicts_with_all = &["choices", "format"])]
+    pub describe: bool,
+
+    /// Explain on stderr how each line is split into fields and which fields each choice resolves to
     #[structopt(short, long)]
-    #[allow(unused)]
     pub debug: bool,
 
+    /// What to do with empty fields between field separators: drop them, keep them (like
+    /// `cut`), or keep only those between non-empty fields. Defaults to drop
+    #[structopt(long, possible_values = EmptyFields::VARIANTS, conflicts_with = "non-greedy")]
+    pub empty_fields: Option<EmptyFields>,
+
+    /// Treat a field separator preceded by this character as part of the field. Requires a
+    /// literal field separator
+    #[structopt(long)]
+    pub escape: Option<char>,
+
     /// Use exclusive ranges, similar to array indexing in many programming languages
     #[structopt(short = "x", long)]
     pub exclusive: bool,
 
-    /// Specify field separator other than whitespace, using Rust `regex` syntax
-    #[structopt(short, long)]
-    pub field_separator: Option<String>,
+    /// Specify field separator other than whitespace, using Rust `regex` syntax. May be repeated
+    /// to split on any of several separators
+    #[structopt(short, long, number_of_values = 1)]
+    pub field_separator: Vec<String>,
+
+    /// Print this text in place of each chosen field that is missing from a line, so that every line
+    /// has the same number of output fields. Ranges without an end are not filled
+    #[structopt(long)]
+    pub fill: Option<String>,
+
+    /// Print each line through a template of literal text and {choice} placeholders, e.g.
+    /// '{0}@{2}:{-1}'. A placeholder can end with a spec like Rust's format!, e.g. {1:>8} or
+    /// {2:08.2}, giving fill, alignment, width, and precision. A spec that is only a number, e.g.
+    /// {1:8} or {1:-2}, is read as the end of a range, so a width alone needs an alignment, e.g.
+    /// {1:<8}
+    #[structopt(long)]
+    pub format: Option<String>,
+
+    /// Print a completion script for bash, zsh, fish, or powershell, or a roff man page, then exit
+    #[structopt(long, hidden = true, possible_values = Generated::VARIANTS)]
+    pub generate: Option<Generated>,
 
     /// Input file
     #[structopt(short, long, parse(from_os_str))]
     pub input: Option<PathBuf>,
 
-    /// Use non-greedy field separators
+    /// Ignore the config file and CHOOSE_OPTS
+    // read by UserConfig::load, before the arguments are parsed
+    #[structopt(long)]
+    #[allow(unused)]
+    pub no_config: bool,
+
+    /// Use non-greedy field separators, keeping empty fields (same as --empty-fields keep)
     #[structopt(short, long)]
     pub non_greedy: bool,
 
@@ -38,13 +98,106 @@ pub struct Opt {
     pub one_indexed: bool,
 
     /// Specify output field separator
-    #[structopt(short, long, parse(from_str = parse::output_field_separator))]
+    #[structopt(short, long, parse(try_from_str = parse::escaped))]
     pub output_field_separator: Option<String>,
 
+    /// Use a preset for a common format, giving its field separator, output separator, index base,
+    /// and names for its fields that can be used in choices and --format placeholders (e.g.
+    /// --preset passwd shell). Built in presets are passwd, group, csv, tsv, apache-combined, nginx,
+    /// syslog, ls-l, and ps, and more can be defined in the config file. Options given on the
+    /// command line override those of the preset. Field names can also be used in --where
+    #[structopt(long)]
+    pub preset: Option<String>,
+
+    /// Print where defaults are read from and the arguments they give, then exit. Defaults are read
+    /// from $XDG_CONFIG_HOME/choose/config.toml, whose keys are the long names of options (e.g.
+    /// one-indexed = true), and then from CHOOSE_OPTS, and are overridden by the command line
+    #[structopt(long)]
+    pub print_config: bool,
+
+    /// Treat a field separator between a pair of this character (e.g. ") as part of the field, as
+    /// in CSV. The quotes are kept in the field. Requires a literal field separator
+    #[structopt(long, conflicts_with = "escape")]
+    pub quote: Option<char>,
+
+    /// Split fields on any one of these characters, without using regex
+    #[structopt(long, conflicts_with_all = &["field-separator", "whitespace-chars", "ascii-whitespace"], parse(try_from_str = parse::escaped))]
+    pub separators: Option<String>,
+
+    /// Read choices and options from a file of directives, one per line: the long name of an option
+    /// followed by its values, if any, or `choices` followed by choices, e.g. `field-separator :` or
+    /// `choices 0 -1`. Lines can have # comments, so that a file starting with
+    /// `#!/usr/bin/env -S choose --spec-file` can be run as a script. Options given after it on the
+    /// command line override those of the file
+    // read by UserConfig::load, and its arguments spliced into the command line
+    #[structopt(long, parse(from_os_str))]
+    #[allow(unused)]
+    pub spec_file: Option<PathBuf>,
+
+    /// After the end of the input, print statistics on stderr: lines read and emitted, lines where a
+    /// choice was empty, fields per line, bytes in and out, and throughput. With --stats=json, print
+    /// them as a single JSON object
+    #[structopt(long, require_equals = true, possible_values = StatsFormat::VARIANTS, conflicts_with = "describe")]
+    pub stats: Option<Option<StatsFormat>>,
+
+    /// Stop with an error (exit code 4) when a choice cannot be satisfied by a line, because an index
+    /// is beyond its fields or a range is empty. With --strict=warn, report such choices on stderr
+    /// but carry on
+    #[structopt(long, require_equals = true, possible_values = Strictness::VARIANTS)]
+    pub strict: Option<Option<Strictness>>,
+
+    /// Remove escape characters from output fields
+    #[structopt(long, requires = "escape")]
+    pub unescape: bool,
+
+    /// Print each field at most once, even if several choices overlap
+    #[structopt(long)]
+    pub unique_fields: bool,
+
+    /// The order to print fields in with --unique-fields: the order choices first mention them, or
+    /// ascending. Defaults to first
+    #[structopt(long, possible_values = FieldOrder::VARIANTS, requires = "unique-fields")]
+    pub unique_order: Option<FieldOrder>,
+
+    /// After the end of the input, warn on stderr about each choice that did not produce a field from
+    /// any line, e.g. an index beyond the fields of every line, with the most fields of a line
+    #[structopt(long, conflicts_with = "describe")]
+    pub warn_unused: bool,
+
+    /// Split fields on runs of any of these characters instead of whitespace
+    #[structopt(long, conflicts_with = "field-separator", parse(try_from_str = parse::escaped))]
+    pub whitespace_chars: Option<String>,
+
+    /// Only print lines whose fields satisfy a predicate, e.g. 3>500, 1=~^GET, or -1!=200.
+    /// If the value is a finite number, the field is compared to it as a number, and a field that
+    /// is not a number only satisfies !=. Otherwise the field is compared to the value as a
+    /// string. =~ and !~ match the field against a regex. Comparisons can be joined with && and
+    /// ||, and the option may be repeated to require every predicate
+    #[structopt(name = "where", long = "where", number_of_values = 1, parse(try_from_str = parse::predicate))]
+    pub predicates: Vec<Predicate>,
+
     /// Fields to print. Either a, a:b, a..b, or a..=b, where a and b are integers. The beginning
     /// or end of a range can be omitted, resulting in including the beginning or end of the line,
     /// respectively. a:b is inclusive of b (unless overridden by -x). a..b is
-    /// exclusive of b and a..=b is inclusive of b.
-    #[structopt(required = true, min_values = 1, parse(try_from_str = parse::choice))]
+    /// exclusive of b and a..=b is inclusive of b. A range can end with :s (e.g. 0:10:2 or ::-1) to
+    /// choose every s-th item, starting from the end of the range if s is negative. Unlike Python,
+    /// the sign of s never changes which items a range covers; write a reversed range (e.g.
+    /// 5:0:2) to step down from a higher index. A choice can be nested as c/sep/c, e.g. 3/:/1, to
+    /// choose from within each chosen field using sep as the field separator. Several choices can
+    /// be given in one argument, separated by commas. A choice prefixed with ! (e.g. !2) excludes
+    /// the fields it covers from the output. A choice can be
+    /// anchored as @/regex/c (or @@/regex/c), e.g. @/--host/+1, making its indexes offsets from
+    /// the first (or last) field matching regex. A choice can end with a subscript choosing
+    /// characters from each chosen field, e.g. 3[0:7] or -1[-4:].
+    #[structopt(name = "choices", required_unless_one = &["format", "describe", "print-config", "generate"], conflicts_with = "format", min_values = 1)]
+    pub choice_args: Vec<String>,
+
+    /// The choices parsed from choice_args, which depend on other options (e.g. --cut-syntax) and
+    /// so are filled in by Config
+    #[structopt(skip)]
     pub choices: Vec<Choice>,
+
+    /// The presets defined in the config file, which are filled in by main
+    #[structopt(skip)]
+    pub presets: Vec<Preset>,
 }
@@ -1,74 +1,603 @@
 use backslash::escape_ascii;
 use regex::Regex;
+use std::ops::Range;
 
-use crate::choice::{Choice, ChoiceKind};
-use crate::error::ParseRangeError;
-use crate::parse_error::ParseError;
+use crate::choice::{Anchor, Choice, ChoiceKind, Nested};
+use crate::config::Separator;
+use crate::parse_error::{ParseError, ParseErrorKind};
+use crate::predicate::{Comparison, Op, Predicate};
+use crate::template::{Align, FormatSpec, Part, Template};
 
 lazy_static! {
-    static ref PARSE_CHOICE_RE: Regex = Regex::new(r"^(-?\d*)(:|\.\.=?)(-?\d*)$").unwrap();
+    static ref PARSE_CHOICE_RE: Regex = Regex::new(r"^([-+]?\d*)(:|\.\.=?)([-+]?\d*)(?::(-?\d*))?$").unwrap();
+    static ref PARSE_CUT_CHOICE_RE: Regex = Regex::new(r"^(\d*)(-?)(\d*)$").unwrap();
+    static ref PARSE_COMPARISON_RE: Regex =
+        Regex::new(r"^\s*([-+]?\d+|[A-Za-z_][A-Za-z0-9_]*)\s*(=~|!~|==|!=|<=|>=|=|<|>)\s*(.*?)\s*$").unwrap();
+    static ref PARSE_COMPARISON_START_RE: Regex = Regex::new(r"^\s*(?:[-+]?\d+|[A-Za-z_][A-Za-z0-9_]*)\s*(=~|!~|==|!=|<=|>=|=|<|>)").unwrap();
+    static ref PARSE_FORMAT_SPEC_RE: Regex =
+        Regex::new(r"^(?:(.)?([<^>]))?(\+)?(0)?(\d+)?(?:\.(\d+))?$").unwrap();
+    static ref CUT_STYLE_RANGE_RE: Regex = Regex::new(r"^(\d+)-(\d*)$").unwrap();
 }
 
+/// Parse a list of comma-separated choices from one argument
+///
+/// Commas within the separator of a nested choice (e.g. `0/,/1`) do not
+/// separate choices. A choice prefixed with `!` excludes the fields it covers
+/// from the output.
+pub fn choices(src: &str, cut_syntax: bool) -> Result<Vec<Choice>, ParseError> {
+    let mut pieces = Vec::new();
+    let mut in_separator = false;
+    let mut start = 0;
+    let mut chars = src.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if chars.peek().map(|(_, c)| *c) == Some('/') => {
+                chars.next();
+            }
+            '/' => in_separator = !in_separator,
+            ',' if !in_separator => {
+                pieces.push((start, &src[start..i]));
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    pieces.push((start, &src[start..]));
+
+    pieces
+        .into_iter()
+        .map(|(offset, piece)| {
+            let (excluded, offset, piece) = match piece.strip_prefix('!') {
+                Some(piece) => (true, offset + 1, piece),
+                None => (false, offset, piece),
+            };
+
+            let choice = match cut_syntax {
+                true => subscripted(piece, cut_choice),
+                false => choice(piece),
+            };
+            let mut choice = choice.map_err(|e| suggest(e, piece, cut_syntax).within(src, offset))?;
+
+            if excluded && choice.nested.is_some() {
+                let kind = ParseErrorKind::Nested("a nested choice cannot be excluded");
+                return Err(ParseError::new(kind, src, offset - 1..offset + piece.len()));
+            }
+
+            choice.excluded = excluded;
+            choice.source = piece.to_string();
+            Ok(choice)
+        })
+        .collect()
+}
+
+/// Add suggestions of what was likely meant to an error in a choice
+fn suggest(mut e: ParseError, src: &str, cut_syntax: bool) -> ParseError {
+    if cut_syntax {
+        if PARSE_CHOICE_RE.is_match(src) || src.parse::<isize>().is_ok_and(|x| x < 0) {
+            e = e.with_help(String::from(
+                "with --cut-syntax, fields are numbered from 1 and ranges are written a-b, e.g. `1-3`",
+            ));
+        }
+    } else if let Some(cap) = CUT_STYLE_RANGE_RE.captures(src) {
+        e = e.with_help(format!("did you mean `{}:{}`?", &cap[1], &cap[2]));
+        e = e.with_help(format!(
+            "to write ranges like `cut -f`, numbered from 1, use `choose --cut-syntax {}`",
+            src
+        ));
+    }
+
+    let list_separators: &[char] = &[';', '|', ' '];
+    if !src.contains('/') && src.contains(list_separators) {
+        let list = src
+            .split(list_separators)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(",");
+        e = e.with_help(format!("separate several choices with commas, e.g. `{}`", list));
+    }
+
+    // choices can start with `-` without `--` before them, so text after `-`
+    // that could not be a choice is most likely a mistyped option
+    if let Some(rest) = src.strip_prefix('-') {
+        if choice(rest).is_ok() {
+            e = e.with_help(format!(
+                "options must come before choices; to pass a choice starting with `-`, put `--` before it, e.g. `choose -- {}`",
+                src
+            ));
+        } else if rest.trim_start_matches('-').starts_with(|c: char| c.is_ascii_alphabetic()) {
+            e = e.with_help(format!("`{}` is not a known option; see `choose --help` for the options", src));
+        }
+    }
+
+    e
+}
+
+/// Build an error for a choice that does not match the choice syntax,
+/// pointing to the first character that does not belong in it, if any
+fn invalid_choice(src: &str, unexpected: Option<(usize, char)>) -> ParseError {
+    match unexpected {
+        Some((i, c)) => ParseError::new(ParseErrorKind::UnexpectedChar(c), src, i..i + c.len_utf8()),
+        None if src.is_empty() => ParseError::whole(ParseErrorKind::Empty, src),
+        None => ParseError::whole(ParseErrorKind::Invalid, src),
+    }
+}
+
+/// Build an error for a number that does not fit in an index
+fn invalid_number(src: &str, e: std::num::ParseIntError, span: Range<usize>) -> ParseError {
+    ParseError::new(ParseErrorKind::Number(e), src, span)
+}
+
+/// Parse a choice written like a `cut -f` list element: a 1-indexed field a,
+/// an inclusive range a-b, or an open range -b or a-
+pub fn cut_choice(src: &str) -> Result<Choice, ParseError> {
+    let cap = match PARSE_CUT_CHOICE_RE.captures(src) {
+        Some(cap) if !(cap[1].is_empty() && cap[3].is_empty()) => cap,
+        _ => {
+            let mut dashes = 0;
+            let unexpected = src.char_indices().find(|(_, c)| {
+                dashes += (*c == '-') as usize;
+                !(c.is_ascii_digit() || *c == '-') || dashes > 1
+            });
+            return Err(invalid_choice(src, unexpected));
+        }
+    };
+
+    let parse_field = |i: usize| -> Result<isize, ParseError> {
+        let m = cap.get(i).unwrap();
+        match m.as_str().parse::<isize>() {
+            Ok(0) => Err(ParseError::new(ParseErrorKind::ZeroField, src, m.range())),
+            Ok(x) => Ok(x - 1),
+            Err(e) => Err(invalid_number(src, e, m.range())),
+        }
+    };
+
+    if cap[2].is_empty() {
+        let field = parse_field(1)?;
+        return Ok(Choice::new(field, field, ChoiceKind::Single));
+    }
+
+    let start = if cap[1].is_empty() { 0 } else { parse_field(1)? };
+    let end = if cap[3].is_empty() { isize::MAX } else { parse_field(3)? };
+
+    if end < start {
+        return Err(ParseError::whole(ParseErrorKind::DecreasingRange, src));
+    }
+
+    Ok(Choice::new(start, end, ChoiceKind::ColonRange))
+}
+
+/// Parse a choice, which may be nested (e.g. `3/:/1/=/-1`) to choose from
+/// within chosen fields using other separators, evaluated left to right
+///
+/// A choice may also be anchored to the first field matching a regex with
+/// `@/regex/choice` (or the last such field with `@@/regex/choice`), in which
+/// case its indexes are offsets from that field, e.g. `@/--host/+1`.
+///
+/// The last choice can have a subscript choosing characters from each of its
+/// fields, e.g. `3[0:7]`. A `/` within a nested separator or anchor regex can
+/// be escaped as `\/`.
 pub fn choice(src: &str) -> Result<Choice, ParseError> {
+    let mut segments = split_nested(src);
+
+    let anchor = match segments[0].1.as_str() {
+        "@" | "@@" if segments.len() >= 3 => {
+            let last = segments[0].1 == "@@";
+            let (span, regex) = segments.remove(1);
+            segments.remove(0);
+            match Regex::new(&regex) {
+                Ok(regex) => Some(Anchor { regex, last }),
+                Err(e) => return Err(ParseError::new(ParseErrorKind::Regex(e), src, span)),
+            }
+        }
+        "@" | "@@" => {
+            let kind = ParseErrorKind::Nested("expected @/regex/choice");
+            return Err(ParseError::whole(kind, src));
+        }
+        _ => None,
+    };
+
+    if segments.len().is_multiple_of(2) {
+        let kind = ParseErrorKind::Nested("missing choice after separator");
+        return Err(ParseError::new(kind, src, src.len()..src.len()));
+    }
+
+    let mut segments = segments.into_iter().rev();
+
+    let (span, leaf) = segments.next().unwrap_or_default();
+    let mut choice = subscripted(&leaf, single_choice).map_err(|e| e.within(src, span.start))?;
+
+    while let Some((separator_span, separator)) = segments.next() {
+        let mut parent = match segments.next() {
+            Some((span, parent)) if parent.ends_with(']') => {
+                let kind = ParseErrorKind::Nested("only the last choice can have a subscript");
+                return Err(ParseError::new(kind, src, span));
+            }
+            Some((span, parent)) => single_choice(&parent).map_err(|e| e.within(src, span.start))?,
+            None => {
+                let kind = ParseErrorKind::Nested("missing choice before separator");
+                return Err(ParseError::new(kind, src, separator_span));
+            }
+        };
+
+        if separator.is_empty() {
+            let kind = ParseErrorKind::Nested("empty separator");
+            return Err(ParseError::new(kind, src, separator_span));
+        }
+
+        let separator = match Separator::new(&separator) {
+            Ok(s) => s,
+            Err(e) => return Err(ParseError::new(ParseErrorKind::Regex(e), src, separator_span)),
+        };
+
+        parent.nested = Some(Box::new(Nested { separator, choice }));
+        choice = parent;
+    }
+
+    if let Some(anchor) = anchor {
+        choice.set_anchor(anchor);
+    }
+
+    Ok(choice)
+}
+
+/// Split a nested choice into alternating choice and separator segments on
+/// each unescaped `/`, along with the span of src each segment was taken from
+fn split_nested(src: &str) -> Vec<(Range<usize>, String)> {
+    let mut segments = vec![(0..src.len(), String::new())];
+    let mut chars = src.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if chars.peek().map(|(_, c)| *c) == Some('/') => {
+                segments.last_mut().unwrap().1.push('/');
+                chars.next();
+            }
+            '/' => {
+                segments.last_mut().unwrap().0.end = i;
+                segments.push((i + 1..src.len(), String::new()));
+            }
+            c => segments.last_mut().unwrap().1.push(c),
+        }
+    }
+    segments
+}
+
+/// Parse a choice that may end with a subscript (e.g. `3[0:7]` or `-1[-4:]`),
+/// a character-wise choice applied to each field the choice selects
+fn subscripted(src: &str, parse: fn(&str) -> Result<Choice, ParseError>) -> Result<Choice, ParseError> {
+    let (choice, subscript) = match src.strip_suffix(']').and_then(|s| s.split_once('[')) {
+        Some((choice, subscript)) => {
+            let offset = choice.len() + 1;
+            let subscript = parse(subscript).map_err(|e| e.within(src, offset))?;
+            (choice, Some(subscript))
+        }
+        None => (src, None),
+    };
+
+    let mut choice = parse(choice).map_err(|e| e.within(src, 0))?;
+    choice.subscript = subscript.map(Box::new);
+    Ok(choice)
+}
+
+fn single_choice(src: &str) -> Result<Choice, ParseError> {
     let cap = match PARSE_CHOICE_RE.captures_iter(src).next() {
         Some(v) => v,
         None => match src.parse() {
             Ok(x) => return Ok(Choice::new(x, x, ChoiceKind::Single)),
-            Err(e) => {
-                eprintln!("failed to parse choice argument: {}", src);
-                return Err(ParseError::ParseIntError(e));
+            Err(e) if src.trim_start_matches(['-', '+']).chars().all(|c| c.is_ascii_digit()) && !src.is_empty() => {
+                return Err(invalid_number(src, e, 0..src.len()));
+            }
+            Err(_) => {
+                let mut prev = None;
+                let unexpected = src.char_indices().find(|(_, c)| {
+                    let misplaced_sign = matches!(c, '-' | '+') && prev.is_some_and(|p: char| p.is_ascii_digit());
+                    prev = Some(*c);
+                    !(c.is_ascii_digit() || "+-:.=".contains(*c)) || misplaced_sign
+                });
+                return Err(invalid_choice(src, unexpected));
             }
         },
     };
 
-    let start = if cap[1].is_empty() {
-        0
-    } else {
-        match cap[1].parse() {
-            Ok(x) => x,
-            Err(e) => {
-                eprintln!("failed to parse range start: {}", &cap[1]);
-                return Err(ParseError::ParseIntError(e));
-            }
+    let parse_index = |i: usize, default: isize| -> Result<isize, ParseError> {
+        match cap.get(i).filter(|m| !m.as_str().is_empty()) {
+            Some(m) => m.as_str().parse().map_err(|e| invalid_number(src, e, m.range())),
+            None => Ok(default),
         }
     };
 
+    let start = parse_index(1, 0)?;
+
     let kind = match &cap[2] {
         ":" => ChoiceKind::ColonRange,
         ".." => ChoiceKind::RustExclusiveRange,
         "..=" => ChoiceKind::RustInclusiveRange,
-        _ => {
-            eprintln!(
-                "failed to parse range: not a valid range separator: {}",
-                &cap[2]
+        _ => return Err(ParseError::new(ParseErrorKind::Invalid, src, cap.get(2).unwrap().range())),
+    };
+
+    let end = parse_index(3, isize::MAX)?;
+
+    let mut choice = Choice::new(start, end, kind);
+
+    if let Some(step) = cap.get(4).filter(|m| !m.as_str().is_empty()) {
+        choice.step = match step.as_str().parse() {
+            Ok(0) => return Err(ParseError::new(ParseErrorKind::ZeroStep, src, step.range())),
+            Ok(x) => x,
+            Err(e) => return Err(invalid_number(src, e, step.range())),
+        };
+    }
+
+    Ok(choice)
+}
+
+/// Get the byte offset of a slice within the string it was taken from
+fn offset_in(src: &str, part: &str) -> usize {
+    part.as_ptr() as usize - src.as_ptr() as usize
+}
+
+/// Parse a predicate on the fields of a line, made of comparisons like `3>500`
+/// or `1=~^GET` joined with `&&` and `||`, where `&&` binds more tightly
+pub fn predicate(src: &str) -> Result<Predicate, ParseError> {
+    let joined = |mut ps: Vec<Predicate>, join: fn(Vec<Predicate>) -> Predicate| match ps.len() {
+        1 => ps.remove(0),
+        _ => join(ps),
+    };
+
+    let mut any = Vec::new();
+    let mut all = Vec::new();
+    for (or, term) in predicate_terms(src) {
+        if or {
+            any.push(joined(std::mem::take(&mut all), Predicate::And));
+        }
+        let term = term.trim();
+        all.push(comparison(term).map_err(|e| e.within(src, offset_in(src, term)))?);
+    }
+    any.push(joined(all, Predicate::And));
+
+    Ok(joined(any, Predicate::Or))
+}
+
+/// Split a predicate into its comparisons, each with whether it follows `||`
+/// rather than `&&`
+///
+/// A regex can contain `||` or `&&` itself (e.g. `0=~ab||cd`), so a `||` or
+/// `&&` after a regex only splits the predicate if a comparison follows it.
+fn predicate_terms(src: &str) -> Vec<(bool, &str)> {
+    let mut terms = Vec::new();
+    let mut or = false;
+    let mut start = 0;
+    let mut from = 0;
+
+    while let Some(i) = [src[from..].find("||"), src[from..].find("&&")].into_iter().flatten().min() {
+        let i = from + i;
+        let in_regex = PARSE_COMPARISON_RE
+            .captures(&src[start..i])
+            .is_some_and(|cap| matches!(&cap[2], "=~" | "!~"));
+        if in_regex && !PARSE_COMPARISON_START_RE.is_match(&src[i + 2..]) {
+            from = i + 2;
+            continue;
+        }
+
+        terms.push((or, &src[start..i]));
+        or = &src[i..i + 2] == "||";
+        start = i + 2;
+        from = start;
+    }
+
+    terms.push((or, &src[start..]));
+    terms
+}
+
+fn comparison(src: &str) -> Result<Predicate, ParseError> {
+    let cap = match PARSE_COMPARISON_RE.captures(src) {
+        Some(cap) => cap,
+        None => {
+            let kind = ParseErrorKind::Predicate(
+                "expected a field index, an operator (==, !=, <, <=, >, >=, =~, !~), and a value",
             );
-            return Err(ParseError::ParseRangeError(ParseRangeError::new(&cap[2])));
+            return Err(ParseError::whole(kind, src));
         }
     };
 
-    let end = if cap[3].is_empty() {
-        isize::max_value()
-    } else {
-        match cap[3].parse() {
-            Ok(x) => x,
-            Err(e) => {
-                eprintln!("failed to parse range end: {}", &cap[3]);
-                return Err(ParseError::ParseIntError(e));
+    // a field name is resolved to an index by the preset that names it
+    let name = cap[1].starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+    let index = match name {
+        true => 0,
+        false => cap[1]
+            .parse()
+            .map_err(|e| invalid_number(src, e, cap.get(1).unwrap().range()))?,
+    };
+    let value = &cap[3];
+
+    let op = match &cap[2] {
+        "=" | "==" => Op::Eq,
+        "!=" => Op::Ne,
+        "<" => Op::Lt,
+        "<=" => Op::Le,
+        ">" => Op::Gt,
+        ">=" => Op::Ge,
+        op => {
+            let regex = Regex::new(value)
+                .map_err(|e| ParseError::new(ParseErrorKind::Regex(e), src, cap.get(3).unwrap().range()))?;
+            match op {
+                "=~" => Op::Match(regex),
+                _ => Op::NotMatch(regex),
+            }
+        }
+    };
+
+    let mut comparison = Comparison::new(index, op, value);
+    if name {
+        comparison.name = Some(cap[1].to_string());
+    }
+    Ok(Predicate::Compare(comparison))
+}
+
+/// Parse an output template of literal text and `{choice}` or `{choice:spec}`
+/// placeholders, where `{{` and `}}` are literal braces
+///
+/// Since choices may contain colons themselves, the text after the last colon
+/// of a placeholder is only taken as a spec if it is not empty or an integer,
+/// so `{1:3}` is a range but `{1:>3}` is a field with a width of 3.
+pub fn template(src: &str, cut_syntax: bool) -> Result<Template, ParseError> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = src.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' if chars.peek().map(|(_, c)| *c) == Some('{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek().map(|(_, c)| *c) == Some('}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let end = match src[i..].find('}') {
+                    Some(end) => i + end,
+                    None => {
+                        let kind = ParseErrorKind::Template("unclosed placeholder");
+                        return Err(ParseError::new(kind, src, i..src.len()));
+                    }
+                };
+                while chars.next_if(|(j, _)| *j <= end).is_some() {}
+
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+                let placeholder = placeholder_part(&src[i + 1..end], cut_syntax);
+                parts.push(placeholder.map_err(|e| e.within(src, i + 1))?);
             }
+            '}' => {
+                let kind = ParseErrorKind::Template("unmatched '}', use '}}' for a literal brace");
+                return Err(ParseError::new(kind, src, i..i + 1));
+            }
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        parts.push(Part::Literal(literal));
+    }
+
+    Ok(Template { parts })
+}
+
+fn placeholder_part(src: &str, cut_syntax: bool) -> Result<Part, ParseError> {
+    // a number after the last colon, e.g. {1:8} or {1:-2}, is the end of a range rather than a
+    // spec, as is nothing, e.g. {1:}, while a signed spec such as {1:+} or {1:+5} is a spec
+    let range_end = |spec: &str| spec.strip_prefix('-').unwrap_or(spec).chars().all(|c| c.is_ascii_digit());
+    let (choice_src, spec) = match src.rsplit_once(':') {
+        Some((choice, spec)) if !range_end(spec) && PARSE_FORMAT_SPEC_RE.is_match(spec) => {
+            let offset = choice.len() + 1;
+            (choice, Some(format_spec(spec).map_err(|e| e.within(src, offset))?))
         }
+        _ => (src, None),
+    };
+
+    if choice_src.is_empty() {
+        return Err(ParseError::whole(ParseErrorKind::Template("empty placeholder"), src));
+    }
+
+    let choices = choices(choice_src, cut_syntax).map_err(|e| e.within(src, 0))?;
+    if choices.iter().any(|c| c.excluded) {
+        let kind = ParseErrorKind::Template("a placeholder cannot exclude fields");
+        return Err(ParseError::new(kind, src, 0..choice_src.len()));
+    }
+
+    Ok(Part::Placeholder(choices, spec))
+}
+
+fn format_spec(src: &str) -> Result<FormatSpec, ParseError> {
+    let cap = match PARSE_FORMAT_SPEC_RE.captures(src) {
+        Some(cap) => cap,
+        None => return Err(ParseError::whole(ParseErrorKind::Template("invalid format spec"), src)),
+    };
+
+    let number = |i: usize| -> Result<Option<usize>, ParseError> {
+        cap.get(i)
+            .map(|m| m.as_str().parse().map_err(|e| invalid_number(src, e, m.range())))
+            .transpose()
     };
 
-    Ok(Choice::new(start, end, kind))
+    Ok(FormatSpec {
+        fill: cap.get(1).and_then(|m| m.as_str().chars().next()),
+        align: cap.get(2).map(|m| match m.as_str() {
+            "<" => Align::Left,
+            "^" => Align::Center,
+            _ => Align::Right,
+        }),
+        plus: cap.get(3).is_some(),
+        zero: cap.get(4).is_some(),
+        width: number(5)?,
+        precision: number(6)?,
+    })
 }
 
-pub fn output_field_separator(src: &str) -> String {
-    escape_ascii(src).unwrap()
+/// Replace the escapes in `src`, e.g. `\t` or `\x7c`, checking each `\x` escape first since
+/// `escape_ascii` panics on one that is not followed by two hex digits
+pub fn escaped(src: &str) -> Result<String, String> {
+    let bytes = src.as_bytes();
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'\\' {
+            match bytes[i + 1] {
+                b'n' | b't' | b'r' | b'\\' | b'0' => i += 1,
+                b'x' => {
+                    let hex = src
+                        .get(i + 2..i + 4)
+                        .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                    match hex {
+                        Some(byte) if byte.is_ascii() => i += 3,
+                        _ => {
+                            return Err(format!(
+                                "invalid escape in '{}': \\x must be followed by two hex digits of an ASCII character",
+                                src
+                            ));
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        i += 1;
+    }
+    escape_ascii(src).map_err(|e| e.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use crate::parse;
 
+    mod parse_escaped_tests {
+        use super::*;
+
+        #[test]
+        fn parse_escapes() {
+            assert_eq!(Ok(String::from("\t|\\|")), parse::escaped("\\t\\x7c\\\\\\x7C"));
+        }
+
+        #[test]
+        fn parse_no_escapes() {
+            assert_eq!(Ok(String::from("ab")), parse::escaped("ab"));
+            assert_eq!(Ok(String::new()), parse::escaped(""));
+        }
+
+        #[test]
+        fn parse_escaped_backslash_before_x() {
+            assert_eq!(Ok(String::from("\\x")), parse::escaped("\\\\x"));
+        }
+
+        #[test]
+        fn parse_invalid_hex_escape() {
+            assert!(parse::escaped("\\x").is_err());
+            assert!(parse::escaped("\\x7").is_err());
+            assert!(parse::escaped("\\xzz").is_err());
+            assert!(parse::escaped("\\xff").is_err());
+        }
+    }
+
     mod parse_choice_tests {
         use super::*;
 
@@ -93,7 +622,7 @@ mod tests {
         #[test]
         fn parse_none_terminated_range() {
             let result = parse::choice("5:").unwrap();
-            assert_eq!((5, isize::max_value()), (result.start, result.end))
+            assert_eq!((5, isize::MAX), (result.start, result.end))
         }
 
         #[test]
@@ -111,7 +640,7 @@ mod tests {
         #[test]
         fn parse_neg_started_none_ended() {
             let result = parse::choice("-3:").unwrap();
-            assert_eq!((-3, isize::max_value()), (result.start, result.end))
+            assert_eq!((-3, isize::MAX), (result.start, result.end))
         }
 
         #[test]
@@ -135,7 +664,7 @@ mod tests {
         #[test]
         fn parse_beginning_to_end_range() {
             let result = parse::choice(":").unwrap();
-            assert_eq!((0, isize::max_value()), (result.start, result.end))
+            assert_eq!((0, isize::MAX), (result.start, result.end))
         }
 
         #[test]
@@ -163,13 +692,13 @@ mod tests {
         #[test]
         fn parse_rust_inclusive_range_no_end() {
             let result = parse::choice("3..=").unwrap();
-            assert_eq!((3, isize::max_value()), (result.start, result.end))
+            assert_eq!((3, isize::MAX), (result.start, result.end))
         }
 
         #[test]
         fn parse_rust_inclusive_range_no_start_or_end() {
             let result = parse::choice("..=").unwrap();
-            assert_eq!((0, isize::max_value()), (result.start, result.end))
+            assert_eq!((0, isize::MAX), (result.start, result.end))
         }
 
         #[test]
@@ -187,7 +716,7 @@ mod tests {
         #[test]
         fn parse_neg_started_none_ended_rust_exclusive() {
             let result = parse::choice("-3..").unwrap();
-            assert_eq!((-3, isize::max_value()), (result.start, result.end))
+            assert_eq!((-3, isize::MAX), (result.start, result.end))
         }
 
         #[test]
@@ -223,13 +752,13 @@ mod tests {
         #[test]
         fn parse_rust_exclusive_range_no_end() {
             let result = parse::choice("3..").unwrap();
-            assert_eq!((3, isize::max_value()), (result.start, result.end))
+            assert_eq!((3, isize::MAX), (result.start, result.end))
         }
 
         #[test]
         fn parse_rust_exclusive_range_no_start_or_end() {
             let result = parse::choice("..").unwrap();
-            assert_eq!((0, isize::max_value()), (result.start, result.end))
+            assert_eq!((0, isize::MAX), (result.start, result.end))
         }
 
         #[test]
@@ -247,7 +776,7 @@ mod tests {
         #[test]
         fn parse_neg_started_none_ended_rust_inclusive() {
             let result = parse::choice("-3..=").unwrap();
-            assert_eq!((-3, isize::max_value()), (result.start, result.end))
+            assert_eq!((-3, isize::MAX), (result.start, result.end))
         }
 
         #[test]
@@ -268,4 +797,663 @@ mod tests {
             assert_eq!((-3, 5), (result.start, result.end))
         }
     }
+
+    mod parse_step_tests {
+        use super::*;
+
+        #[test]
+        fn parse_no_step() {
+            let result = parse::choice("0:10").unwrap();
+            assert_eq!(1, result.step)
+        }
+
+        #[test]
+        fn parse_colon_range_step() {
+            let result = parse::choice("0:10:2").unwrap();
+            assert_eq!((0, 10, 2), (result.start, result.end, result.step))
+        }
+
+        #[test]
+        fn parse_open_range_negative_step() {
+            let result = parse::choice("::-1").unwrap();
+            assert_eq!((0, isize::MAX, -1), (result.start, result.end, result.step))
+        }
+
+        #[test]
+        fn parse_empty_step() {
+            let result = parse::choice("1:3:").unwrap();
+            assert_eq!((1, 3, 1), (result.start, result.end, result.step))
+        }
+
+        #[test]
+        fn parse_negative_indexes_step() {
+            let result = parse::choice("-1:-5:2").unwrap();
+            assert_eq!((-1, -5, 2), (result.start, result.end, result.step))
+        }
+
+        #[test]
+        fn parse_rust_exclusive_range_step() {
+            let result = parse::choice("0..10:3").unwrap();
+            assert_eq!((0, 10, 3), (result.start, result.end, result.step))
+        }
+
+        #[test]
+        fn parse_rust_inclusive_range_step() {
+            let result = parse::choice("..=10:-3").unwrap();
+            assert_eq!((0, 10, -3), (result.start, result.end, result.step))
+        }
+
+        #[test]
+        fn parse_zero_step() {
+            assert!(parse::choice("0:10:0").is_err());
+        }
+
+        #[test]
+        fn parse_bad_step() {
+            assert!(parse::choice("0:10:a").is_err());
+            assert!(parse::choice("0:10:2:1").is_err());
+        }
+    }
+
+    mod parse_anchored_choice_tests {
+        use super::*;
+
+        #[test]
+        fn parse_not_anchored() {
+            let result = parse::choice("1").unwrap();
+            assert!(result.anchor.is_none())
+        }
+
+        #[test]
+        fn parse_anchored_single() {
+            let result = parse::choice("@/--host/+1").unwrap();
+            let anchor = result.anchor.unwrap();
+            assert_eq!("--host", anchor.regex.as_str());
+            assert!(!anchor.last);
+            assert_eq!((1, 1), (result.start, result.end));
+        }
+
+        #[test]
+        fn parse_anchored_last() {
+            let result = parse::choice("@@/^ERR/:").unwrap();
+            let anchor = result.anchor.unwrap();
+            assert_eq!("^ERR", anchor.regex.as_str());
+            assert!(anchor.last);
+            assert_eq!((0, isize::MAX), (result.start, result.end));
+        }
+
+        #[test]
+        fn parse_anchored_negative_offset() {
+            let result = parse::choice("@/x/-2:+2").unwrap();
+            assert_eq!((-2, 2), (result.start, result.end));
+            assert!(!result.has_negative_index());
+            assert!(!result.is_reverse_range());
+        }
+
+        #[test]
+        fn parse_anchored_reversed() {
+            let result = parse::choice("@/x/1:-1").unwrap();
+            assert!(result.is_reverse_range());
+        }
+
+        #[test]
+        fn parse_anchored_nested() {
+            let result = parse::choice("@/user/0/=/1").unwrap();
+            assert!(result.anchor.is_some());
+            assert!(result.nested.unwrap().choice.anchor.is_none());
+        }
+
+        #[test]
+        fn parse_anchored_escaped_slash() {
+            let result = parse::choice("@/^\\/usr/0").unwrap();
+            assert_eq!("^/usr", result.anchor.unwrap().regex.as_str());
+        }
+
+        #[test]
+        fn parse_anchored_missing_choice() {
+            assert!(parse::choice("@/x").is_err());
+            assert!(parse::choice("@").is_err());
+        }
+
+        #[test]
+        fn parse_anchored_bad_regex() {
+            assert!(parse::choice("@/[/0").is_err());
+        }
+    }
+
+    mod parse_choice_list_tests {
+        use super::*;
+        use crate::choice::Choice;
+
+        fn start_end(choices: Vec<Choice>) -> Vec<(isize, isize)> {
+            choices.iter().map(|c| (c.start, c.end)).collect()
+        }
+
+        #[test]
+        fn parse_single_choice_list() {
+            let result = parse::choices("3", false).unwrap();
+            assert_eq!(vec![(3, 3)], start_end(result))
+        }
+
+        #[test]
+        fn parse_comma_separated_choices() {
+            let result = parse::choices("1,3,5:7", false).unwrap();
+            assert_eq!(vec![(1, 1), (3, 3), (5, 7)], start_end(result))
+        }
+
+        #[test]
+        fn parse_comma_separated_negative_choices() {
+            let result = parse::choices("-1,-3:", false).unwrap();
+            assert_eq!(vec![(-1, -1), (-3, isize::MAX)], start_end(result))
+        }
+
+        #[test]
+        fn parse_comma_separated_nested_choices() {
+            let result = parse::choices("0/,/1,2", false).unwrap();
+            assert_eq!(vec![(0, 0), (2, 2)], start_end(result))
+        }
+
+        #[test]
+        fn parse_comma_separated_bad_choice() {
+            assert!(parse::choices("1,,3", false).is_err());
+            assert!(parse::choices("1,", false).is_err());
+            assert!(parse::choices("1,d", false).is_err());
+        }
+
+        #[test]
+        fn parse_excluded_choices() {
+            let result = parse::choices("!2,1:3,!-1", false).unwrap();
+            assert_eq!(
+                vec![true, false, true],
+                result.iter().map(|c| c.excluded).collect::<Vec<_>>()
+            );
+            assert_eq!(vec![(2, 2), (1, 3), (-1, -1)], start_end(result))
+        }
+
+        #[test]
+        fn parse_excluded_cut_choice() {
+            let result = parse::choices("!2-3", true).unwrap();
+            assert!(result[0].excluded);
+            assert_eq!(vec![(1, 2)], start_end(result))
+        }
+
+        #[test]
+        fn parse_excluded_nested_choice() {
+            assert!(parse::choices("!0/,/1", false).is_err());
+        }
+
+        #[test]
+        fn parse_cut_choice_list() {
+            let result = parse::choices("1,3,5-7", true).unwrap();
+            assert_eq!(vec![(0, 0), (2, 2), (4, 6)], start_end(result))
+        }
+    }
+
+    mod parse_cut_choice_tests {
+        use super::*;
+        use crate::choice::ChoiceKind;
+
+        #[test]
+        fn parse_cut_single() {
+            let result = parse::cut_choice("3").unwrap();
+            assert_eq!((2, 2), (result.start, result.end));
+            assert_eq!(ChoiceKind::Single, result.kind);
+        }
+
+        #[test]
+        fn parse_cut_range() {
+            let result = parse::cut_choice("5-7").unwrap();
+            assert_eq!((4, 6), (result.start, result.end));
+            assert_eq!(ChoiceKind::ColonRange, result.kind);
+        }
+
+        #[test]
+        fn parse_cut_open_start() {
+            let result = parse::cut_choice("-3").unwrap();
+            assert_eq!((0, 2), (result.start, result.end))
+        }
+
+        #[test]
+        fn parse_cut_open_end() {
+            let result = parse::cut_choice("3-").unwrap();
+            assert_eq!((2, isize::MAX), (result.start, result.end))
+        }
+
+        #[test]
+        fn parse_cut_zero() {
+            assert!(parse::cut_choice("0").is_err());
+            assert!(parse::cut_choice("0-3").is_err());
+        }
+
+        #[test]
+        fn parse_cut_decreasing_range() {
+            assert!(parse::cut_choice("7-5").is_err());
+        }
+
+        #[test]
+        fn parse_cut_bad_choice() {
+            assert!(parse::cut_choice("").is_err());
+            assert!(parse::cut_choice("-").is_err());
+            assert!(parse::cut_choice("1:3").is_err());
+            assert!(parse::cut_choice("a-b").is_err());
+        }
+    }
+
+    mod parse_nested_choice_tests {
+        use super::*;
+        use crate::config::Separator;
+
+        #[test]
+        fn parse_not_nested() {
+            let result = parse::choice("3").unwrap();
+            assert!(result.nested.is_none())
+        }
+
+        #[test]
+        fn parse_nested_once() {
+            let result = parse::choice("3/:/1").unwrap();
+            assert_eq!((3, 3), (result.start, result.end));
+            let nested = result.nested.unwrap();
+            assert!(matches!(nested.separator, Separator::LiteralChar(':')));
+            assert_eq!((1, 1), (nested.choice.start, nested.choice.end));
+            assert!(nested.choice.nested.is_none());
+        }
+
+        #[test]
+        fn parse_nested_twice() {
+            let result = parse::choice("3/:/1/=/-1").unwrap();
+            let nested = result.nested.unwrap();
+            let nested = nested.choice.nested.unwrap();
+            assert!(matches!(nested.separator, Separator::LiteralChar('=')));
+            assert_eq!((-1, -1), (nested.choice.start, nested.choice.end));
+        }
+
+        #[test]
+        fn parse_nested_ranges() {
+            let result = parse::choice("1:3/,/-2:").unwrap();
+            assert_eq!((1, 3), (result.start, result.end));
+            let nested = result.nested.unwrap();
+            assert_eq!((-2, isize::MAX), (nested.choice.start, nested.choice.end));
+        }
+
+        #[test]
+        fn parse_nested_regex_separator() {
+            let result = parse::choice("0/[,;]/1").unwrap();
+            assert!(matches!(result.nested.unwrap().separator, Separator::Regex(_)));
+        }
+
+        #[test]
+        fn parse_nested_escaped_slash_separator() {
+            let result = parse::choice("0/\\//1").unwrap();
+            assert!(matches!(result.nested.unwrap().separator, Separator::LiteralChar('/')));
+        }
+
+        #[test]
+        fn parse_nested_missing_choice() {
+            assert!(parse::choice("3/:").is_err());
+            assert!(parse::choice("3/:/").is_err());
+        }
+
+        #[test]
+        fn parse_nested_empty_separator() {
+            assert!(parse::choice("3//1").is_err());
+        }
+
+        #[test]
+        fn parse_nested_bad_separator() {
+            assert!(parse::choice("3/[/1").is_err());
+        }
+    }
+
+    mod parse_predicate_tests {
+        use super::*;
+        use crate::predicate::{Op, Predicate};
+
+        fn comparison(src: &str) -> (isize, String) {
+            match parse::predicate(src).unwrap() {
+                Predicate::Compare(c) => (c.index, c.value),
+                p => panic!("expected a comparison, got {:?}", p),
+            }
+        }
+
+        #[test]
+        fn parse_comparison() {
+            assert_eq!((3, String::from("500")), comparison("3>500"));
+            assert_eq!((-1, String::from("200")), comparison("-1!=200"));
+            assert_eq!((1, String::from("^GET")), comparison("1=~^GET"));
+        }
+
+        #[test]
+        fn parse_comparison_spaces() {
+            assert_eq!((2, String::from("a b")), comparison(" 2 == a b "));
+        }
+
+        #[test]
+        fn parse_comparison_ops() {
+            let op = |src| match parse::predicate(src).unwrap() {
+                Predicate::Compare(c) => c.op,
+                _ => unreachable!(),
+            };
+            assert!(matches!(op("0=a"), Op::Eq));
+            assert!(matches!(op("0==a"), Op::Eq));
+            assert!(matches!(op("0<=a"), Op::Le));
+            assert!(matches!(op("0<a"), Op::Lt));
+            assert!(matches!(op("0>=a"), Op::Ge));
+            assert!(matches!(op("0!~a"), Op::NotMatch(_)));
+        }
+
+        #[test]
+        fn parse_and_or() {
+            match parse::predicate("0==a && 1==b || 2==c").unwrap() {
+                Predicate::Or(any) => {
+                    assert_eq!(2, any.len());
+                    assert!(matches!(&any[0], Predicate::And(all) if all.len() == 2));
+                    assert!(matches!(&any[1], Predicate::Compare(_)));
+                }
+                p => panic!("expected an or, got {:?}", p),
+            }
+        }
+
+        #[test]
+        fn parse_regex_with_or() {
+            assert_eq!((0, String::from("ab||cd")), comparison("0=~ab||cd"));
+            assert_eq!((0, String::from("a&&b")), comparison("0!~a&&b"));
+        }
+
+        #[test]
+        fn parse_regex_then_comparison() {
+            match parse::predicate("0=~ab||cd || 1==x && 2=~y&&z").unwrap() {
+                Predicate::Or(any) => {
+                    assert!(matches!(&any[0], Predicate::Compare(c) if c.value == "ab||cd"));
+                    assert!(matches!(&any[1], Predicate::And(all) if all.len() == 2));
+                }
+                p => panic!("expected an or, got {:?}", p),
+            }
+        }
+
+        #[test]
+        fn parse_named_comparison() {
+            match parse::predicate("shell=/bin/bash").unwrap() {
+                Predicate::Compare(c) => {
+                    assert_eq!(Some(String::from("shell")), c.name);
+                    assert_eq!("/bin/bash", c.value);
+                }
+                p => panic!("expected a comparison, got {:?}", p),
+            }
+        }
+
+        #[test]
+        fn parse_regex_then_named_comparison() {
+            assert!(matches!(parse::predicate("0=~a || uid>0").unwrap(), Predicate::Or(any) if any.len() == 2));
+        }
+
+        #[test]
+        fn parse_missing_op() {
+            assert!(parse::predicate("3").is_err());
+            assert!(parse::predicate("a.b>3").is_err());
+            assert!(parse::predicate("3>1 &&").is_err());
+        }
+
+        #[test]
+        fn parse_bad_regex() {
+            assert!(parse::predicate("1=~[").is_err());
+        }
+    }
+
+    mod parse_template_tests {
+        use super::*;
+        use crate::template::{Align, FormatSpec, Part};
+
+        fn parts(src: &str) -> Vec<Part> {
+            parse::template(src, false).unwrap().parts
+        }
+
+        #[test]
+        fn parse_literals_and_placeholders() {
+            let parts = parts("{0}@{2}:{-1}");
+            assert_eq!(5, parts.len());
+            assert!(matches!(&parts[0], Part::Placeholder(c, None) if c[0].start == 0));
+            assert!(matches!(&parts[1], Part::Literal(s) if s == "@"));
+            assert!(matches!(&parts[3], Part::Literal(s) if s == ":"));
+            assert!(matches!(&parts[4], Part::Placeholder(c, None) if c[0].start == -1));
+        }
+
+        #[test]
+        fn parse_escaped_braces() {
+            let parts = parts("{{{0}}}");
+            assert_eq!(3, parts.len());
+            assert!(matches!(&parts[0], Part::Literal(s) if s == "{"));
+            assert!(matches!(&parts[2], Part::Literal(s) if s == "}"));
+        }
+
+        #[test]
+        fn parse_range_placeholder() {
+            let range = parts("{1:3}");
+            assert!(matches!(&range[0], Part::Placeholder(c, None) if (c[0].start, c[0].end) == (1, 3)));
+            let open = parts("{1:}");
+            assert!(matches!(&open[0], Part::Placeholder(c, None) if c[0].end == isize::MAX));
+        }
+
+        #[test]
+        fn parse_placeholder_spec() {
+            match &parts("{1:*^10.2}")[0] {
+                Part::Placeholder(c, Some(spec)) => {
+                    assert_eq!(1, c[0].start);
+                    let expected = FormatSpec {
+                        fill: Some('*'),
+                        align: Some(Align::Center),
+                        width: Some(10),
+                        precision: Some(2),
+                        ..FormatSpec::default()
+                    };
+                    assert_eq!(&expected, spec);
+                }
+                p => panic!("expected a placeholder with a spec, got {:?}", p),
+            }
+        }
+
+        #[test]
+        fn parse_range_placeholder_spec() {
+            match &parts("{0:2:>8}")[0] {
+                Part::Placeholder(c, Some(spec)) => {
+                    assert_eq!((0, 2), (c[0].start, c[0].end));
+                    assert_eq!((Some(Align::Right), Some(8)), (spec.align, spec.width));
+                }
+                p => panic!("expected a placeholder with a spec, got {:?}", p),
+            }
+        }
+
+        #[test]
+        fn parse_zero_padded_spec() {
+            match &parts("{0:+08.1}")[0] {
+                Part::Placeholder(_, Some(spec)) => {
+                    assert!(spec.plus && spec.zero);
+                    assert_eq!((Some(8), Some(1)), (spec.width, spec.precision));
+                }
+                p => panic!("expected a placeholder with a spec, got {:?}", p),
+            }
+        }
+
+        #[test]
+        fn parse_signed_spec() {
+            match &parts("{2:+}")[0] {
+                Part::Placeholder(c, Some(spec)) => {
+                    assert_eq!((2, 2), (c[0].start, c[0].end));
+                    assert!(spec.plus);
+                }
+                p => panic!("expected a placeholder with a spec, got {:?}", p),
+            }
+            match &parts("{2:+5}")[0] {
+                Part::Placeholder(c, Some(spec)) => {
+                    assert_eq!((2, 2), (c[0].start, c[0].end));
+                    assert_eq!((true, Some(5)), (spec.plus, spec.width));
+                }
+                p => panic!("expected a placeholder with a spec, got {:?}", p),
+            }
+        }
+
+        #[test]
+        fn parse_negative_range_end_placeholder() {
+            let range = parts("{1:-2}");
+            assert!(matches!(&range[0], Part::Placeholder(c, None) if (c[0].start, c[0].end) == (1, -2)));
+        }
+
+        #[test]
+        fn parse_bad_templates() {
+            assert!(parse::template("{0", false).is_err());
+            assert!(parse::template("0}", false).is_err());
+            assert!(parse::template("{}", false).is_err());
+            assert!(parse::template("{!0}", false).is_err());
+            assert!(parse::template("{a}", false).is_err());
+        }
+    }
+
+    mod parse_subscript_tests {
+        use super::*;
+
+        fn subscript(src: &str) -> (isize, isize, isize, isize) {
+            let choice = parse::choice(src).unwrap();
+            let subscript = choice.subscript.unwrap();
+            (choice.start, choice.end, subscript.start, subscript.end)
+        }
+
+        #[test]
+        fn parse_subscript() {
+            assert_eq!((3, 3, 0, 7), subscript("3[0:7]"));
+            assert_eq!((-1, -1, -4, isize::MAX), subscript("-1[-4:]"));
+            assert_eq!((0, 2, 1, 1), subscript("0:2[1]"));
+        }
+
+        #[test]
+        fn parse_no_subscript() {
+            assert!(parse::choice("3").unwrap().subscript.is_none());
+        }
+
+        #[test]
+        fn parse_nested_subscript() {
+            let choice = parse::choice("3/:/1[0:2]").unwrap();
+            assert!(choice.subscript.is_none());
+            assert!(choice.nested.unwrap().choice.subscript.is_some());
+        }
+
+        #[test]
+        fn parse_subscript_on_parent() {
+            assert!(parse::choice("3[0:2]/:/1").is_err());
+        }
+
+        #[test]
+        fn parse_cut_subscript() {
+            let choice = &parse::choices("3[1-7]", true).unwrap()[0];
+            let subscript = choice.subscript.as_ref().unwrap();
+            assert_eq!((2, 0, 6), (choice.start, subscript.start, subscript.end));
+        }
+
+        #[test]
+        fn parse_bad_subscript() {
+            assert!(parse::choice("3[]").is_err());
+            assert!(parse::choice("3[a]").is_err());
+            assert!(parse::choice("3[0:2").is_err());
+        }
+    }
+
+    mod parse_error_tests {
+        use super::*;
+        use crate::parse_error::ParseErrorKind;
+
+        fn error(src: &str) -> parse::ParseError {
+            parse::choices(src, false).unwrap_err()
+        }
+
+        #[test]
+        fn span_unexpected_char() {
+            let e = error("1-3");
+            assert!(matches!(e.kind, ParseErrorKind::UnexpectedChar('-')));
+            assert_eq!(1..2, e.span);
+        }
+
+        #[test]
+        fn span_in_choice_list() {
+            let e = error("0,2;4");
+            assert_eq!(("0,2;4", 3..4), (e.src.as_str(), e.span));
+        }
+
+        #[test]
+        fn span_excluded_choice() {
+            assert_eq!(4..5, error("0,!1x").span);
+        }
+
+        #[test]
+        fn span_nested_choice() {
+            assert_eq!(4..5, error("3/:/a").span);
+            assert_eq!(2..3, error("3/[/1").span);
+            assert_eq!(3..3, error("3/:").span);
+        }
+
+        #[test]
+        fn span_subscript() {
+            assert_eq!(4..5, error("3[0:a]").span);
+        }
+
+        #[test]
+        fn span_step() {
+            let e = error("0:3:0");
+            assert!(matches!(e.kind, ParseErrorKind::ZeroStep));
+            assert_eq!(4..5, e.span);
+        }
+
+        #[test]
+        fn span_cut_zero_field() {
+            let e = parse::choices("1,0-3", true).unwrap_err();
+            assert!(matches!(e.kind, ParseErrorKind::ZeroField));
+            assert_eq!(2..3, e.span);
+        }
+
+        #[test]
+        fn span_predicate() {
+            let e = parse::predicate("1>2 && 3").unwrap_err();
+            assert_eq!(7..8, e.span);
+        }
+
+        #[test]
+        fn span_template() {
+            assert_eq!(2..3, parse::template("x{a}", false).unwrap_err().span);
+            assert_eq!(1..4, parse::template("x{0:", false).unwrap_err().span);
+        }
+
+        #[test]
+        fn suggest_colon_range() {
+            let help = error("1-3").help;
+            assert_eq!("did you mean `1:3`?", help[0]);
+            assert!(help[1].contains("--cut-syntax 1-3"));
+        }
+
+        #[test]
+        fn suggest_commas() {
+            assert_eq!(vec!["separate several choices with commas, e.g. `2,4`"], error("2;4").help);
+        }
+
+        #[test]
+        fn suggest_unknown_option() {
+            assert_eq!(vec!["`-q` is not a known option; see `choose --help` for the options"], error("-q").help);
+            assert!(error("-x3").help[0].contains("not a known option"));
+            assert!(error("--bogus").help[0].contains("not a known option"));
+        }
+
+        #[test]
+        fn suggest_double_dash_only_before_a_choice() {
+            assert!(error("--1").help[0].contains("`choose -- --1`"));
+            assert!(!error("-q").help[0].contains("`--`"));
+        }
+
+        #[test]
+        fn suggest_cut_syntax_ranges() {
+            let e = parse::choices("1:3", true).unwrap_err();
+            assert!(e.help[0].contains("ranges are written a-b"));
+        }
+
+        #[test]
+        fn no_suggestion() {
+            assert!(error("1::2::3").help.is_empty());
+        }
+    }
 }
@@ -1,14 +1,156 @@
+use std::fmt;
+use std::ops::Range;
+
+/// ParseError is an error in an argument, such as a choice, along with the
+/// span of the argument it was found at and suggestions of what was likely
+/// meant instead
 #[derive(Debug)]
-pub enum ParseError {
-    ParseIntError(std::num::ParseIntError),
-    ParseRangeError(crate::error::ParseRangeError),
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub src: String,
+    pub span: Range<usize>,
+    pub help: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum ParseErrorKind {
+    Empty,
+    UnexpectedChar(char),
+    Invalid,
+    Number(std::num::ParseIntError),
+    ZeroStep,
+    ZeroField,
+    DecreasingRange,
+    Nested(&'static str),
+    Regex(regex::Error),
+    Predicate(&'static str),
+    Template(&'static str),
+}
+
+impl ParseError {
+    /// Create an error for the span of src at which it was found
+    pub fn new(kind: ParseErrorKind, src: &str, span: Range<usize>) -> Self {
+        ParseError {
+            kind,
+            src: src.to_string(),
+            span,
+            help: Vec::new(),
+        }
+    }
+
+    /// Create an error for the whole of src
+    pub fn whole(kind: ParseErrorKind, src: &str) -> Self {
+        ParseError::new(kind, src, 0..src.len())
+    }
+
+    /// Make the error relative to a larger source, of which the source it was
+    /// found in is the part starting at offset
+    pub fn within(mut self, src: &str, offset: usize) -> Self {
+        self.span = self.span.start + offset..self.span.end + offset;
+        self.src = src.to_string();
+        self
+    }
+
+    pub fn with_help(mut self, help: String) -> Self {
+        self.help.push(help);
+        self
+    }
 }
 
-impl ToString for ParseError {
-    fn to_string(&self) -> String {
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ParseError::ParseIntError(e) => e.to_string(),
-            ParseError::ParseRangeError(e) => e.to_string(),
+            ParseErrorKind::Empty => write!(f, "empty choice"),
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c),
+            ParseErrorKind::Invalid => write!(f, "not a valid choice"),
+            ParseErrorKind::Number(e) => write!(f, "invalid number: {}", e),
+            ParseErrorKind::ZeroStep => write!(f, "step cannot be zero"),
+            ParseErrorKind::ZeroField => write!(f, "fields are numbered from 1"),
+            ParseErrorKind::DecreasingRange => write!(f, "invalid decreasing range"),
+            ParseErrorKind::Nested(e) => write!(f, "invalid nested choice: {}", e),
+            // syntax errors from regex draw their own caret, so only their
+            // final line describing the error is kept
+            ParseErrorKind::Regex(regex::Error::Syntax(e)) => {
+                let e = e.lines().last().unwrap_or_default();
+                write!(f, "invalid regex: {}", e.trim_start_matches("error: "))
+            }
+            ParseErrorKind::Regex(e) => write!(f, "invalid regex: {}", e),
+            ParseErrorKind::Predicate(e) => write!(f, "invalid predicate: {}", e),
+            ParseErrorKind::Template(e) => write!(f, "invalid format template: {}", e),
+        }
+    }
+}
+
+/// Render the error with a caret under the span of the source it was found at,
+/// followed by any suggestions, e.g.
+///
+/// ```text
+/// unexpected character '-'
+///     1-3
+///      ^
+/// help: did you mean `1:3`?
+/// ```
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.kind)?;
+        writeln!(f, "    {}", self.src)?;
+
+        let start = self.src.get(..self.span.start).map_or(0, |s| s.chars().count());
+        let len = self.src.get(self.span.clone()).map_or(0, |s| s.chars().count());
+        write!(f, "    {}{}", " ".repeat(start), "^".repeat(len.max(1)))?;
+
+        for help in &self.help {
+            write!(f, "\nhelp: {}", help)?;
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn display_caret() {
+        let e = ParseError::new(ParseErrorKind::UnexpectedChar('-'), "1-3", 1..2);
+        assert_eq!("unexpected character '-'\n    1-3\n     ^", e.to_string());
+    }
+
+    #[test]
+    fn display_caret_span() {
+        let e = ParseError::whole(ParseErrorKind::Invalid, "1::2::3");
+        assert_eq!("not a valid choice\n    1::2::3\n    ^^^^^^^", e.to_string());
+    }
+
+    #[test]
+    fn display_caret_empty_span() {
+        let e = ParseError::new(ParseErrorKind::Empty, "0,", 2..2);
+        assert_eq!("empty choice\n    0,\n      ^", e.to_string());
+    }
+
+    #[test]
+    fn display_caret_multibyte() {
+        let e = ParseError::new(ParseErrorKind::UnexpectedChar('x'), "é:x", 3..4);
+        assert_eq!("unexpected character 'x'\n    é:x\n      ^", e.to_string());
+    }
+
+    #[test]
+    fn display_help() {
+        let e = ParseError::new(ParseErrorKind::UnexpectedChar('-'), "1-3", 1..2)
+            .with_help(String::from("did you mean `1:3`?"));
+        assert!(e.to_string().ends_with("\nhelp: did you mean `1:3`?"));
+    }
+
+    #[test]
+    fn display_regex() {
+        let pattern = String::from("[");
+        let e = ParseError::new(ParseErrorKind::Regex(regex::Regex::new(&pattern).unwrap_err()), "3/[/1", 2..3);
+        assert_eq!("invalid regex: unclosed character class\n    3/[/1\n      ^", e.to_string());
+    }
+
+    #[test]
+    fn within() {
+        let e = ParseError::new(ParseErrorKind::UnexpectedChar('-'), "1-3", 1..2).within("0,1-3", 2);
+        assert_eq!(("0,1-3", 3..4), (e.src.as_str(), e.span));
     }
 }
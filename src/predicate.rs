@@ -0,0 +1,249 @@
+use regex::Regex;
+use std::cmp::Ordering;
+use std::convert::TryInto;
+
+/// Predicate is a condition on the fields of a line (e.g. `3>500`) that
+/// decides whether the line is printed at all
+#[derive(Debug)]
+pub enum Predicate {
+    Compare(Comparison),
+    And(Vec<Predicate>),
+    Or(Vec<Predicate>),
+}
+
+/// Comparison compares a single field of a line, chosen with the same index
+/// syntax as a single choice, to a value
+#[derive(Debug)]
+pub struct Comparison {
+    pub index: isize,
+    /// The name of the field (e.g. `shell`), until it is replaced by the index
+    /// of the field in a preset
+    pub name: Option<String>,
+    pub op: Op,
+    pub value: String,
+    number: Option<f64>,
+}
+
+#[derive(Debug)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Match(Regex),
+    NotMatch(Regex),
+}
+
+impl Predicate {
+    /// Evaluate the predicate against the fields of a line
+    pub fn matches<S: AsRef<str>>(&self, fields: &[S]) -> bool {
+        match self {
+            Predicate::Compare(c) => c.matches(fields),
+            Predicate::And(ps) => ps.iter().all(|p| p.matches(fields)),
+            Predicate::Or(ps) => ps.iter().any(|p| p.matches(fields)),
+        }
+    }
+
+    /// Convert the indexes of every comparison from one-indexed to
+    /// zero-indexed
+    pub fn one_indexed(&mut self) {
+        match self {
+            Predicate::Compare(c) => {
+                if c.index > 0 {
+                    c.index -= 1;
+                }
+            }
+            Predicate::And(ps) | Predicate::Or(ps) => ps.iter_mut().for_each(Predicate::one_indexed),
+        }
+    }
+
+    /// Replace the name of the field of every comparison that has one with
+    /// its zero-based index, returning the first name that `index_of` does
+    /// not know
+    pub fn resolve_names<F: Fn(&str) -> Option<isize>>(&mut self, index_of: &F) -> Result<(), String> {
+        match self {
+            Predicate::Compare(c) => {
+                if let Some(name) = c.name.take() {
+                    c.index = index_of(&name).ok_or(name)?;
+                }
+                Ok(())
+            }
+            Predicate::And(ps) | Predicate::Or(ps) => ps.iter_mut().try_for_each(|p| p.resolve_names(index_of)),
+        }
+    }
+}
+
+impl Comparison {
+    pub fn new(index: isize, op: Op, value: &str) -> Self {
+        Comparison {
+            index,
+            name: None,
+            op,
+            value: value.to_string(),
+            number: number(value),
+        }
+    }
+
+    /// Compare the chosen field to the value, numerically if the value is a
+    /// number
+    ///
+    /// A field that is not a number is unordered against a number, so only
+    /// `!=` is true of it. A comparison against a field that does not exist in
+    /// the line is always false.
+    fn matches<S: AsRef<str>>(&self, fields: &[S]) -> bool {
+        let field = match self.field(fields) {
+            Some(field) => field,
+            None => return false,
+        };
+
+        let ordering = || match (number(field), self.number) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            (None, Some(_)) => None,
+            _ => Some(field.cmp(self.value.as_str())),
+        };
+
+        match &self.op {
+            Op::Eq => ordering() == Some(Ordering::Equal),
+            Op::Ne => ordering() != Some(Ordering::Equal),
+            Op::Lt => ordering() == Some(Ordering::Less),
+            Op::Le => matches!(ordering(), Some(Ordering::Less | Ordering::Equal)),
+            Op::Gt => ordering() == Some(Ordering::Greater),
+            Op::Ge => matches!(ordering(), Some(Ordering::Greater | Ordering::Equal)),
+            Op::Match(r) => r.is_match(field),
+            Op::NotMatch(r) => !r.is_match(field),
+        }
+    }
+
+    fn field<'a, S: AsRef<str>>(&self, fields: &'a [S]) -> Option<&'a str> {
+        let i: usize = if self.index < 0 {
+            fields.len().checked_sub(self.index.unsigned_abs())?
+        } else {
+            self.index.try_into().ok()?
+        };
+        fields.get(i).map(AsRef::as_ref)
+    }
+}
+
+/// Parse text as a finite decimal number, so that e.g. `inf` and `nan` are
+/// compared as text
+pub(crate) fn number(s: &str) -> Option<f64> {
+    let decimal = s.bytes().any(|b| b.is_ascii_digit())
+        && s.bytes().all(|b| b.is_ascii_digit() || matches!(b, b'+' | b'-' | b'.' | b'e' | b'E'));
+    if !decimal {
+        return None;
+    }
+    s.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn compare(index: isize, op: Op, value: &str) -> Predicate {
+        Predicate::Compare(Comparison::new(index, op, value))
+    }
+
+    const FIELDS: &[&str] = &["GET", "/index.html", "200", "5120", "0.25"];
+
+    #[test]
+    fn compare_numeric() {
+        assert!(compare(3, Op::Gt, "500").matches(FIELDS));
+        assert!(compare(3, Op::Ge, "5120").matches(FIELDS));
+        assert!(!compare(3, Op::Lt, "500").matches(FIELDS));
+        assert!(compare(4, Op::Le, "0.5").matches(FIELDS));
+        assert!(compare(2, Op::Eq, "200.0").matches(FIELDS));
+    }
+
+    #[test]
+    fn compare_numeric_not_lexicographic() {
+        assert!(compare(3, Op::Gt, "600").matches(FIELDS));
+    }
+
+    #[test]
+    fn compare_non_finite_as_string() {
+        let fields = &["inf", "nan", "1e999", "-Infinity"];
+        for i in 0..4 {
+            assert!(!compare(i, Op::Gt, "5").matches(fields));
+            assert!(!compare(i, Op::Lt, "5").matches(fields));
+            assert!(compare(i, Op::Ne, "5").matches(fields));
+        }
+        assert!(compare(0, Op::Eq, "inf").matches(fields));
+        assert!(compare(1, Op::Gt, "inf").matches(fields));
+    }
+
+    #[test]
+    fn compare_text_to_number() {
+        assert!(!compare(0, Op::Gt, "5").matches(FIELDS));
+        assert!(!compare(0, Op::Eq, "5").matches(FIELDS));
+        assert!(compare(0, Op::Ne, "5").matches(FIELDS));
+    }
+
+    #[test]
+    fn number_is_finite_decimal() {
+        assert_eq!(Some(-1.5), number("-1.5"));
+        assert_eq!(Some(2000.0), number("2e3"));
+        assert_eq!(None, number("inf"));
+        assert_eq!(None, number("NaN"));
+        assert_eq!(None, number("1e999"));
+        assert_eq!(None, number("e"));
+    }
+
+    #[test]
+    fn compare_string() {
+        assert!(compare(0, Op::Eq, "GET").matches(FIELDS));
+        assert!(compare(0, Op::Ne, "POST").matches(FIELDS));
+        assert!(compare(1, Op::Lt, "/z").matches(FIELDS));
+    }
+
+    #[test]
+    fn compare_regex() {
+        let regex = || Regex::new("^/index").unwrap();
+        assert!(compare(1, Op::Match(regex()), "").matches(FIELDS));
+        assert!(!compare(1, Op::NotMatch(regex()), "").matches(FIELDS));
+    }
+
+    #[test]
+    fn compare_negative_index() {
+        assert!(compare(-3, Op::Ne, "404").matches(FIELDS));
+        assert!(compare(-5, Op::Eq, "GET").matches(FIELDS));
+    }
+
+    #[test]
+    fn compare_missing_field() {
+        assert!(!compare(5, Op::Ne, "x").matches(FIELDS));
+        assert!(!compare(-6, Op::Ne, "x").matches(FIELDS));
+    }
+
+    #[test]
+    fn combine_and_or() {
+        let and = Predicate::And(vec![compare(0, Op::Eq, "GET"), compare(2, Op::Eq, "404")]);
+        assert!(!and.matches(FIELDS));
+        let or = Predicate::Or(vec![compare(0, Op::Eq, "POST"), compare(2, Op::Eq, "200")]);
+        assert!(or.matches(FIELDS));
+    }
+
+    #[test]
+    fn one_indexed() {
+        let mut p = Predicate::And(vec![compare(1, Op::Eq, "GET"), compare(-1, Op::Eq, "0.25")]);
+        p.one_indexed();
+        assert!(p.matches(FIELDS));
+    }
+
+    #[test]
+    fn resolve_names() {
+        let mut p = crate::parse::predicate("uid>0 && 0==x").unwrap();
+        let index_of = |name: &str| (name == "uid").then_some(2);
+        p.resolve_names(&index_of).unwrap();
+        assert!(p.matches(&["x", "y", "1"]));
+        assert!(!p.matches(&["x", "y", "0"]));
+    }
+
+    #[test]
+    fn resolve_unknown_name() {
+        let mut p = crate::parse::predicate("0==x || uidd>0").unwrap();
+        assert_eq!(Err(String::from("uidd")), p.resolve_names(&|_: &str| None));
+    }
+
+}
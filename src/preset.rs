@@ -0,0 +1,464 @@
+use regex::Regex;
+
+use crate::empty_fields::EmptyFields;
+use crate::error::Error;
+use crate::opt::Opt;
+use crate::parse_error::{ParseError, ParseErrorKind};
+
+lazy_static! {
+    static ref ALIAS_RE: Regex = Regex::new(r"[A-Za-z_][A-Za-z0-9_]*").unwrap();
+}
+
+/// Preset is a named bundle of options for a common format, with names for
+/// its fields that can be used in place of indexes, e.g. `--preset passwd
+/// shell`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    pub field_separator: Option<String>,
+    /// A regex matching a whole line, whose capture groups are the fields of
+    /// the line, for formats with fields that contain the separator
+    pub pattern: Option<String>,
+    pub output_field_separator: Option<String>,
+    /// The character that quotes fields containing the field separator
+    pub quote: Option<char>,
+    pub one_indexed: bool,
+    pub empty_fields: Option<EmptyFields>,
+    /// The names of fields and their zero-based indexes
+    pub fields: Vec<(String, isize)>,
+}
+
+/// The names of the built in presets
+pub const BUILTIN: &[&str] = &[
+    "passwd",
+    "group",
+    "csv",
+    "tsv",
+    "apache-combined",
+    "nginx",
+    "syslog",
+    "ls-l",
+    "ps",
+];
+
+impl Preset {
+    /// Find a preset by name among the user's presets and then the built in
+    /// ones, so that a user's preset can replace a built in one
+    pub fn find(name: &str, user: &[Preset]) -> Result<Preset, Error> {
+        if let Some(preset) = user.iter().find(|p| p.name == name) {
+            return Ok(preset.clone());
+        }
+
+        builtin(name).ok_or_else(|| {
+            let names = user.iter().map(|p| p.name.as_str()).chain(BUILTIN.iter().copied());
+            Error::Config(format!(
+                "unknown preset '{}', expected one of: {}",
+                name,
+                names.collect::<Vec<_>>().join(", ")
+            ))
+        })
+    }
+
+    /// Set the options of the preset that were not given on the command line
+    pub fn apply(&self, opt: &mut Opt) {
+        if !has_separator(opt) {
+            opt.field_separator.extend(self.field_separator.clone());
+
+            // the quotes are only known to surround the preset's separator
+            if opt.quote.is_none() && opt.escape.is_none() {
+                opt.quote = self.quote;
+            }
+        }
+
+        if opt.output_field_separator.is_none() {
+            opt.output_field_separator = self.output_field_separator.clone();
+        }
+
+        // --cut-syntax is already one-indexed
+        if self.one_indexed && !opt.cut_syntax {
+            opt.one_indexed = true;
+        }
+
+        if opt.empty_fields.is_none() && !opt.non_greedy {
+            opt.empty_fields = self.empty_fields;
+        }
+    }
+
+    /// The pattern of the preset, if it is used to split lines, i.e. no
+    /// separator was given on the command line
+    pub fn pattern_for(&self, opt: &Opt) -> Option<&str> {
+        self.pattern.as_deref().filter(|_| !has_separator(opt))
+    }
+
+    /// Replace the names of fields in a choice (or a `--format` template) with
+    /// their indexes, counting from 1 if `one_based`
+    ///
+    /// A name is replaced where an index could be: on its own, or as either
+    /// end of a range, before any nested choice or subscript, e.g. `shell`,
+    /// `!uid:gid` or `home[0:4]`. Anchors are left alone.
+    pub fn resolve(&self, src: &str, one_based: bool) -> String {
+        if self.fields.is_empty() {
+            return src.to_string();
+        }
+
+        let mut resolved = String::with_capacity(src.len());
+        let mut last = 0;
+        let mut in_index = true;
+        let mut chars = src.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                // an escaped brace in a template is literal text
+                '{' | '}' if chars.peek().map(|(_, next)| *next) == Some(c) => {
+                    chars.next();
+                    in_index = false;
+                    continue;
+                }
+                ',' | '{' => in_index = true,
+                '@' | '/' | '[' | '}' => in_index = false,
+                _ => (),
+            }
+
+            if !in_index || i < last {
+                continue;
+            }
+
+            let word = match ALIAS_RE.find(&src[i..]) {
+                Some(m) if m.start() == 0 => m.as_str(),
+                _ => continue,
+            };
+            let preceded_by_word = src[..i]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_');
+            if preceded_by_word {
+                continue;
+            }
+
+            if let Some(index) = self.index_of(word) {
+                let index = if one_based && index >= 0 { index + 1 } else { index };
+                resolved.push_str(&src[last..i]);
+                resolved.push_str(&index.to_string());
+                last = i + word.len();
+            }
+        }
+        resolved.push_str(&src[last..]);
+        resolved
+    }
+
+    /// The zero-based index of the field with a name
+    pub fn index_of(&self, name: &str) -> Option<isize> {
+        self.fields.iter().find(|(field, _)| field == name).map(|(_, index)| *index)
+    }
+
+    /// Suggest the names of the fields of the preset for an error in a choice
+    /// that looks like it has a misspelled name
+    pub fn suggest(&self, e: ParseError) -> ParseError {
+        match e.kind {
+            ParseErrorKind::UnexpectedChar(c) if c.is_ascii_alphabetic() || c == '_' => e.with_help(self.names()),
+            _ => e,
+        }
+    }
+
+    /// A description of the names of the fields of the preset, for messages
+    pub fn names(&self) -> String {
+        let names = self.fields.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+        match names.as_slice() {
+            [] => format!("the {} preset has no field names", self.name),
+            names => format!("the {} preset names the fields {}", self.name, names.join(", ")),
+        }
+    }
+
+    /// Read a preset from a table of the config file, e.g.
+    ///
+    /// ```toml
+    /// [presets.access]
+    /// pattern = '^(\S+) \S+ \S+ \[([^]]*)\] "([^"]*)"'
+    /// output-field-separator = "\t"
+    /// one-indexed = true
+    /// fields = { host = 1, time = 2, request = 3 }
+    /// ```
+    ///
+    /// The indexes of fields are in the index base of the preset. A preset with
+    /// a field-separator can also have a quote character.
+    pub fn from_toml(name: &str, value: &toml::Value) -> Result<Preset, String> {
+        let table = value
+            .as_table()
+            .ok_or_else(|| format!("preset {} must be a table", name))?;
+
+        let string = |key: &str| -> Result<Option<String>, String> {
+            match table.get(key) {
+                None => Ok(None),
+                Some(toml::Value::String(s)) => Ok(Some(s.clone())),
+                Some(_) => Err(format!("{} of preset {} must be a string", key, name)),
+            }
+        };
+
+        let mut preset = Preset {
+            name: name.to_string(),
+            field_separator: string("field-separator")?,
+            pattern: string("pattern")?,
+            output_field_separator: string("output-field-separator")?,
+            ..Preset::default()
+        };
+
+        preset.quote = match string("quote")? {
+            None => None,
+            Some(quote) => {
+                let mut chars = quote.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(c),
+                    _ => return Err(format!("quote of preset {} must be a single character", name)),
+                }
+            }
+        };
+
+        if preset.quote.is_some() && preset.field_separator.is_none() {
+            return Err(format!("preset {} needs a field-separator to have a quote", name));
+        }
+
+        if preset.field_separator.is_some() && preset.pattern.is_some() {
+            return Err(format!("preset {} cannot have both a field-separator and a pattern", name));
+        }
+
+        if let Some(pattern) = &preset.pattern {
+            Regex::new(pattern).map_err(|e| format!("invalid pattern of preset {}: {}", name, e))?;
+        }
+
+        preset.one_indexed = match table.get("one-indexed") {
+            None => false,
+            Some(toml::Value::Boolean(b)) => *b,
+            Some(_) => return Err(format!("one-indexed of preset {} must be a boolean", name)),
+        };
+
+        preset.empty_fields = string("empty-fields")?
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(|e| format!("empty-fields of preset {}: {}", name, e))?;
+
+        if let Some(fields) = table.get("fields") {
+            let fields = fields
+                .as_table()
+                .ok_or_else(|| format!("fields of preset {} must be a table", name))?;
+            for (field, index) in fields {
+                if ALIAS_RE.find(field).is_none_or(|m| m.as_str() != field) {
+                    return Err(format!("invalid field name {} in preset {}", field, name));
+                }
+                let index = index
+                    .as_integer()
+                    .and_then(|i| isize::try_from(i).ok())
+                    .ok_or_else(|| format!("field {} of preset {} must be an integer", field, name))?;
+                let index = if preset.one_indexed && index > 0 { index - 1 } else { index };
+                preset.fields.push((field.clone(), index));
+            }
+        }
+
+        let known = [
+            "field-separator",
+            "pattern",
+            "output-field-separator",
+            "quote",
+            "one-indexed",
+            "empty-fields",
+            "fields",
+        ];
+        if let Some(key) = table.keys().find(|key| !known.contains(&key.as_str())) {
+            return Err(format!("unknown key {} in preset {}", key, name));
+        }
+
+        Ok(preset)
+    }
+}
+
+/// Whether any option choosing how to split lines was given
+fn has_separator(opt: &Opt) -> bool {
+    !opt.field_separator.is_empty() || opt.separators.is_some() || opt.whitespace_chars.is_some() || opt.ascii_whitespace
+}
+
+fn fields(names: &[&str]) -> Vec<(String, isize)> {
+    names.iter().zip(0..).map(|(name, i)| (name.to_string(), i)).collect()
+}
+
+fn separated(name: &str, separator: &str, names: &[&str]) -> Preset {
+    Preset {
+        name: name.to_string(),
+        field_separator: Some(separator.to_string()),
+        output_field_separator: Some(separator.to_string()),
+        one_indexed: true,
+        empty_fields: Some(EmptyFields::Keep),
+        fields: fields(names),
+        ..Preset::default()
+    }
+}
+
+fn patterned(name: &str, pattern: &str, names: &[&str]) -> Preset {
+    Preset {
+        name: name.to_string(),
+        pattern: Some(pattern.to_string()),
+        fields: fields(names),
+        ..Preset::default()
+    }
+}
+
+/// The built in preset with a name
+///
+/// The colon and comma separated formats are one-indexed, like `cut` and
+/// spreadsheet columns, and keep empty fields so that fields keep their
+/// positions. CSV fields can be quoted to contain commas. The log and command
+/// output formats are split with a pattern, as their fields can contain
+/// spaces.
+fn builtin(name: &str) -> Option<Preset> {
+    let preset = match name {
+        "passwd" => separated(name, ":", &["user", "password", "uid", "gid", "gecos", "home", "shell"]),
+        "group" => separated(name, ":", &["group", "password", "gid", "members"]),
+        "csv" => Preset {
+            quote: Some('"'),
+            ..separated(name, ",", &[])
+        },
+        "tsv" => separated(name, "\t", &[]),
+        "apache-combined" => patterned(
+            name,
+            r#"^(\S+) (\S+) (\S+) \[([^\]]*)\] "((?:[^"\\]|\\.)*)" (\S+) (\S+)(?: "((?:[^"\\]|\\.)*)" "((?:[^"\\]|\\.)*)")?"#,
+            &["host", "ident", "user", "time", "request", "status", "bytes", "referer", "agent"],
+        ),
+        "nginx" => patterned(
+            name,
+            r#"^(\S+) - (\S+) \[([^\]]*)\] "((?:[^"\\]|\\.)*)" (\S+) (\S+) "((?:[^"\\]|\\.)*)" "((?:[^"\\]|\\.)*)""#,
+            &["addr", "user", "time", "request", "status", "bytes", "referer", "agent"],
+        ),
+        "syslog" => patterned(
+            name,
+            r"^(\w{3} +\d+ \d{2}:\d{2}:\d{2}) (\S+) ([^:\[\s]+)(?:\[(\d+)\])?: ?(.*)$",
+            &["time", "host", "program", "pid", "message"],
+        ),
+        "ls-l" => patterned(
+            name,
+            r"^(\S+)\s+(\d+)\s+(\S+)\s+(\S+)\s+(\d+(?:,\s*\d+)?)\s+(\w{3}\s+\d+\s+(?:\d{2}:\d{2}|\d{4}))\s(.*)$",
+            &["mode", "links", "owner", "group", "size", "date", "name"],
+        ),
+        "ps" => patterned(
+            name,
+            r"^\s*(\S+)\s+(\S+)\s+(\S+)\s+(\S+)\s+(\S+)\s+(\S+)\s+(\S+)\s+(\S+)\s+(\S+)\s+(\S+)\s+(.*)$",
+            &["user", "pid", "cpu", "mem", "vsz", "rss", "tty", "stat", "start", "time", "command"],
+        ),
+        _ => return None,
+    };
+    Some(preset)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn passwd() -> Preset {
+        builtin("passwd").unwrap()
+    }
+
+    #[test]
+    fn every_builtin_exists() {
+        for name in BUILTIN {
+            assert_eq!(*name, builtin(name).unwrap().name);
+        }
+    }
+
+    #[test]
+    fn builtin_patterns_compile() {
+        for name in BUILTIN {
+            if let Some(pattern) = builtin(name).unwrap().pattern {
+                let regex = Regex::new(&pattern).unwrap();
+                assert!(regex.captures_len() > 1, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn builtin_pattern_field_names_match_groups() {
+        for name in BUILTIN {
+            let preset = builtin(name).unwrap();
+            if let Some(pattern) = &preset.pattern {
+                assert_eq!(Regex::new(pattern).unwrap().captures_len() - 1, preset.fields.len(), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn find_unknown() {
+        let e = Preset::find("nope", &[]).unwrap_err();
+        assert!(e.to_string().starts_with("unknown preset 'nope', expected one of: passwd, group"));
+    }
+
+    #[test]
+    fn find_user_preset_before_builtin() {
+        let user = Preset {
+            name: String::from("csv"),
+            field_separator: Some(String::from(";")),
+            ..Preset::default()
+        };
+        assert_eq!(Some(String::from(";")), Preset::find("csv", &[user]).unwrap().field_separator);
+    }
+
+    #[test]
+    fn resolve_single() {
+        assert_eq!("7", passwd().resolve("shell", true));
+        assert_eq!("6", passwd().resolve("shell", false));
+    }
+
+    #[test]
+    fn resolve_ranges_and_lists() {
+        assert_eq!("1,3:4,!6", passwd().resolve("user,uid:gid,!home", true));
+        assert_eq!("3..=4", passwd().resolve("uid..=gid", true));
+        assert_eq!("1:-1", passwd().resolve("user:-1", true));
+    }
+
+    #[test]
+    fn resolve_subscript_but_not_inside() {
+        assert_eq!("6[0:4]", passwd().resolve("home[0:4]", true));
+        assert_eq!("5/,/user", passwd().resolve("gecos/,/user", true));
+    }
+
+    #[test]
+    fn resolve_leaves_anchors_and_unknown_words() {
+        assert_eq!("@/shell/+1", passwd().resolve("@/shell/+1", true));
+        assert_eq!("users", passwd().resolve("users", true));
+        assert_eq!("1user", passwd().resolve("1user", true));
+    }
+
+    #[test]
+    fn resolve_template() {
+        assert_eq!("{1} uses {7:>10} {{shell}}", passwd().resolve("{user} uses {shell:>10} {{shell}}", true));
+    }
+
+    #[test]
+    fn from_toml() {
+        let value: toml::Value = "pattern = '^(\\S+) (\\S+)$'\none-indexed = true\nfields = { host = 1, path = 2 }\n"
+            .parse()
+            .unwrap();
+        let preset = Preset::from_toml("access", &value).unwrap();
+        assert_eq!(Some(String::from(r"^(\S+) (\S+)$")), preset.pattern);
+        assert_eq!(vec![(String::from("host"), 0), (String::from("path"), 1)], preset.fields);
+    }
+
+    #[test]
+    fn from_toml_quote() {
+        let value: toml::Value = "field-separator = ';'\nquote = \"'\"\n".parse().unwrap();
+        assert_eq!(Some('\''), Preset::from_toml("semi", &value).unwrap().quote);
+    }
+
+    #[test]
+    fn from_toml_invalid() {
+        let invalid = [
+            "pattern = '('",
+            "pattern = '(a)'\nfield-separator = ':'",
+            "one-indexed = 'yes'",
+            "empty-fields = 'sometimes'",
+            "fields = { 2x = 1 }",
+            "fields = { a = 'b' }",
+            "separator = ':'",
+            "field-separator = ';'\nquote = '\"\"'",
+            "quote = '\"'",
+        ];
+        for src in invalid {
+            let value: toml::Value = src.parse().unwrap();
+            assert!(Preset::from_toml("p", &value).is_err(), "{}", src);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+/// QuotedSplit splits a line on a literal separator, except where the
+/// separator is between a pair of quote characters, as in a CSV line
+///
+/// The quotes are kept in the fields, so that they can be printed as they
+/// were written. A doubled quote inside a quoted field (e.g. `"a""b"`) closes
+/// and reopens the quotes, so it is part of the field too.
+pub struct QuotedSplit<'a, 'b> {
+    remainder: Option<&'a str>,
+    separator: &'b str,
+    quote: char,
+}
+
+impl<'a, 'b> QuotedSplit<'a, 'b> {
+    pub fn new(line: &'a str, separator: &'b str, quote: char) -> Self {
+        QuotedSplit {
+            remainder: Some(line),
+            separator,
+            quote,
+        }
+    }
+}
+
+impl<'a> Iterator for QuotedSplit<'a, '_> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remainder = self.remainder?;
+
+        let mut quoted = false;
+        for (i, c) in remainder.char_indices() {
+            if c == self.quote {
+                quoted = !quoted;
+            } else if !quoted && remainder[i..].starts_with(self.separator) {
+                self.remainder = Some(&remainder[i + self.separator.len()..]);
+                return Some(&remainder[..i]);
+            }
+        }
+
+        self.remainder = None;
+        Some(remainder)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn split<'a>(line: &'a str, separator: &str) -> Vec<&'a str> {
+        QuotedSplit::new(line, separator, '"').collect()
+    }
+
+    #[test]
+    fn split_without_quotes() {
+        assert_eq!(vec!["a", "b", "c"], split("a,b,c", ","));
+    }
+
+    #[test]
+    fn split_quoted_separator() {
+        assert_eq!(vec!["a", "\"b,c\"", "d"], split("a,\"b,c\",d", ","));
+    }
+
+    #[test]
+    fn split_doubled_quote() {
+        assert_eq!(vec!["\"a\"\",\"\"b\"", "c"], split("\"a\"\",\"\"b\",c", ","));
+    }
+
+    #[test]
+    fn split_empty_fields() {
+        assert_eq!(vec!["", "\"\"", ""], split(",\"\",", ","));
+    }
+
+    #[test]
+    fn split_unclosed_quote() {
+        assert_eq!(vec!["a", "\"b,c"], split("a,\"b,c", ","));
+    }
+
+    #[test]
+    fn split_quoted_string_separator() {
+        assert_eq!(vec!["a", "\"b::c\""], split("a::\"b::c\"", "::"));
+    }
+}
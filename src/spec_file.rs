@@ -0,0 +1,182 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::Error;
+use crate::result::Result;
+
+/// SpecFile is a recipe of choices and options read from a file given with
+/// `--spec-file`, with one directive per line, e.g.
+///
+/// ```text
+/// #!/usr/bin/env -S choose --spec-file
+/// # the user and shell of each account
+/// field-separator :
+/// output-field-separator '\t'
+/// choices 0 -1
+/// ```
+///
+/// A directive is the long name of an option followed by its values, if any,
+/// which are split like the words of a shell command, or `choices` followed by
+/// choices. `#` starts a comment, so a file can start with a shebang and be run
+/// as a script.
+#[derive(Debug)]
+pub struct SpecFile {
+    pub path: PathBuf,
+    pub args: Vec<OsString>,
+}
+
+impl SpecFile {
+    pub fn read(path: PathBuf) -> Result<Self> {
+        let src = fs::read_to_string(&path).map_err(|e| Error::Open(path.clone(), e))?;
+        let args = parse(&src).map_err(|e| Error::Config(format!("invalid spec file {}: {}", path.display(), e)))?;
+        Ok(SpecFile { path, args })
+    }
+
+    /// The paths of the spec files given on a command line, in order
+    pub fn paths(cli: &[OsString]) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        let mut args = cli.iter().skip(1).take_while(|arg| *arg != "--");
+        while let Some(arg) = args.next() {
+            if arg == "--spec-file" {
+                paths.extend(args.next().map(PathBuf::from));
+            } else if let Some(path) = arg.to_str().and_then(|arg| arg.strip_prefix("--spec-file=")) {
+                paths.push(PathBuf::from(path));
+            }
+        }
+        paths
+    }
+}
+
+/// Insert the arguments of each spec file after the `--spec-file` argument
+/// that names it, so that the options after it on the command line override
+/// those of the spec file
+pub fn splice(cli: &[OsString], spec_files: &[SpecFile]) -> Vec<OsString> {
+    let mut spec_files = spec_files.iter();
+    let mut args = Vec::new();
+    let mut cli = cli.iter();
+
+    while let Some(arg) = cli.next() {
+        args.push(arg.clone());
+        if arg == "--" {
+            break;
+        }
+        let named = if arg == "--spec-file" {
+            args.extend(cli.next().cloned());
+            true
+        } else {
+            arg.to_str().is_some_and(|arg| arg.starts_with("--spec-file="))
+        };
+        if named && let Some(spec_file) = spec_files.next() {
+            args.extend(spec_file.args.iter().cloned());
+        }
+    }
+
+    args.extend(cli.cloned());
+    args
+}
+
+/// Parse the directives of a spec file into arguments
+fn parse(src: &str) -> std::result::Result<Vec<OsString>, String> {
+    let mut args = Vec::new();
+
+    for (i, line) in src.lines().enumerate() {
+        let words = shell_words::split(line).map_err(|e| format!("line {}: {}", i + 1, e))?;
+        let (directive, values) = match words.split_first() {
+            Some(words) => words,
+            None => continue,
+        };
+
+        if directive == "choices" {
+            if values.is_empty() {
+                return Err(format!("line {}: choices needs at least one choice", i + 1));
+            }
+            args.extend(values.iter().map(OsString::from));
+        } else if directive.starts_with('-') {
+            return Err(format!(
+                "line {}: directive '{}' should be the long name of an option without hyphens",
+                i + 1,
+                directive
+            ));
+        } else if values.is_empty() {
+            args.push(OsString::from(format!("--{}", directive)));
+        } else {
+            args.extend(values.iter().map(|value| OsString::from(format!("--{}={}", directive, value))));
+        }
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    fn spec_file(args: &[&str]) -> SpecFile {
+        SpecFile {
+            path: PathBuf::from("spec"),
+            args: self::args(args),
+        }
+    }
+
+    #[test]
+    fn parse_directives() {
+        let src = "#!/usr/bin/env -S choose --spec-file\n\n# users\nfield-separator :\none-indexed\noutput-field-separator '\\t' # tab\nchoices 1 -1\n";
+        assert_eq!(
+            args(&["--field-separator=:", "--one-indexed", "--output-field-separator=\\t", "1", "-1"]),
+            parse(src).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_repeated_values() {
+        assert_eq!(args(&["--where=0=a", "--where=1=b"]), parse("where 0=a 1=b").unwrap());
+    }
+
+    #[test]
+    fn parse_quoted_format() {
+        assert_eq!(args(&["--format={0} {2}"]), parse("format '{0} {2}'").unwrap());
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse("format '{0}").unwrap_err().starts_with("line 1: "));
+        assert!(parse("\n--one-indexed").unwrap_err().starts_with("line 2: "));
+        assert!(parse("choices").is_err());
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(
+            vec![PathBuf::from("a"), PathBuf::from("b")],
+            SpecFile::paths(&args(&["choose", "--spec-file", "a", "-o", ",", "--spec-file=b", "--", "--spec-file", "c"]))
+        );
+    }
+
+    #[test]
+    fn splice_after_spec_file() {
+        let spec_files = [spec_file(&["--one-indexed", "1"]), spec_file(&["2"])];
+        assert_eq!(
+            args(&["choose", "--spec-file", "a", "--one-indexed", "1", "-o", ",", "--spec-file=b", "2", "3"]),
+            splice(&args(&["choose", "--spec-file", "a", "-o", ",", "--spec-file=b", "3"]), &spec_files)
+        );
+    }
+
+    #[test]
+    fn splice_stops_at_double_hyphen() {
+        assert_eq!(
+            args(&["choose", "--", "--spec-file", "a"]),
+            splice(&args(&["choose", "--", "--spec-file", "a"]), &[spec_file(&["1"])])
+        );
+    }
+
+    #[test]
+    fn read_missing() {
+        let e = SpecFile::read(PathBuf::from("/nonexistent/choose-spec")).unwrap_err();
+        assert_eq!(3, e.exit_code());
+    }
+}
@@ -0,0 +1,206 @@
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::result::Result;
+
+/// StatsFormat is how `--stats` prints its statistics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatsFormat {
+    /// One statistic per line, for people
+    Text,
+    /// A single JSON object, for monitoring
+    Json,
+}
+
+impl FromStr for StatsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(StatsFormat::Text),
+            "json" => Ok(StatsFormat::Json),
+            _ => Err(format!("invalid stats format: {}", s)),
+        }
+    }
+}
+
+impl StatsFormat {
+    pub const VARIANTS: &'static [&'static str] = &["text", "json"];
+}
+
+/// Stats are the statistics gathered about a run for `--stats`
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub lines_read: usize,
+    pub lines_emitted: usize,
+    /// Lines emitted where at least one choice resolved to no fields
+    pub lines_with_empty_choice: usize,
+    pub min_fields: Option<usize>,
+    pub max_fields: usize,
+    total_fields: usize,
+    pub bytes_in: usize,
+    pub bytes_out: usize,
+}
+
+impl Stats {
+    /// Add a line of `bytes` bytes read from the input, including its line
+    /// ending, and whether it was emitted
+    pub fn add_line(&mut self, config: &Config, line: &str, bytes: usize, emitted: bool) -> Result<()> {
+        let fields = config.fields(line);
+
+        self.lines_read += 1;
+        self.bytes_in += bytes;
+        self.min_fields = Some(self.min_fields.map_or(fields.len(), |min| min.min(fields.len())));
+        self.max_fields = self.max_fields.max(fields.len());
+        self.total_fields += fields.len();
+
+        if !emitted {
+            return Ok(());
+        }
+
+        self.lines_emitted += 1;
+        for choice in config.choices().filter(|c| !c.excluded) {
+            if choice.resolve(&fields)?.is_empty() {
+                self.lines_with_empty_choice += 1;
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// The average number of fields in the lines read
+    pub fn average_fields(&self) -> f64 {
+        if self.lines_read == 0 {
+            return 0.0;
+        }
+        self.total_fields as f64 / self.lines_read as f64
+    }
+
+    /// Print the statistics for a run that took `elapsed`
+    pub fn print<W: Write>(&self, out: &mut W, format: StatsFormat, elapsed: Duration) -> io::Result<()> {
+        let seconds = elapsed.as_secs_f64();
+        let per_second = |n: usize| if seconds > 0.0 { n as f64 / seconds } else { 0.0 };
+
+        match format {
+            StatsFormat::Text => {
+                writeln!(out, "lines read: {}", self.lines_read)?;
+                writeln!(out, "lines emitted: {}", self.lines_emitted)?;
+                writeln!(out, "lines with an empty choice: {}", self.lines_with_empty_choice)?;
+                writeln!(
+                    out,
+                    "fields per line: min {}, max {}, average {:.2}",
+                    self.min_fields.unwrap_or(0),
+                    self.max_fields,
+                    self.average_fields()
+                )?;
+                writeln!(out, "bytes in: {}", self.bytes_in)?;
+                writeln!(out, "bytes out: {}", self.bytes_out)?;
+                writeln!(out, "elapsed: {:.3}s", seconds)?;
+                writeln!(
+                    out,
+                    "throughput: {:.0} lines/s, {:.2} MB/s",
+                    per_second(self.lines_read),
+                    per_second(self.bytes_in) / 1e6
+                )
+            }
+            StatsFormat::Json => writeln!(
+                out,
+                concat!(
+                    "{{\"lines_read\":{},\"lines_emitted\":{},\"lines_with_empty_choice\":{},",
+                    "\"fields_min\":{},\"fields_max\":{},\"fields_average\":{},",
+                    "\"bytes_in\":{},\"bytes_out\":{},\"elapsed_seconds\":{},",
+                    "\"lines_per_second\":{},\"bytes_per_second\":{}}}"
+                ),
+                self.lines_read,
+                self.lines_emitted,
+                self.lines_with_empty_choice,
+                self.min_fields.unwrap_or(0),
+                self.max_fields,
+                self.average_fields(),
+                self.bytes_in,
+                self.bytes_out,
+                seconds,
+                per_second(self.lines_read),
+                per_second(self.bytes_in)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::opt::Opt;
+    use structopt::StructOpt;
+
+    fn stats(args: Vec<&str>, lines: &[(&str, bool)]) -> Stats {
+        let config = Config::new(Opt::from_iter(args)).unwrap();
+        let mut stats = Stats::default();
+        for (line, emitted) in lines {
+            stats.add_line(&config, line, line.len() + 1, *emitted).unwrap();
+        }
+        stats
+    }
+
+    fn print(stats: &Stats, format: StatsFormat) -> String {
+        let mut out = Vec::new();
+        stats.print(&mut out, format, Duration::from_secs(2)).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn count_lines_and_fields() {
+        let stats = stats(vec!["choose", "0"], &[("a b c", true), ("a", false), ("a b", true)]);
+        assert_eq!((3, 2), (stats.lines_read, stats.lines_emitted));
+        assert_eq!((Some(1), 3), (stats.min_fields, stats.max_fields));
+        assert_eq!(2.0, stats.average_fields());
+        assert_eq!(12, stats.bytes_in);
+    }
+
+    #[test]
+    fn count_empty_choices_of_emitted_lines() {
+        let stats = stats(vec!["choose", "0", "2"], &[("a b c", true), ("a b", true), ("a", false)]);
+        assert_eq!(1, stats.lines_with_empty_choice);
+    }
+
+    #[test]
+    fn excluded_choices_are_not_empty() {
+        let stats = stats(vec!["choose", "0", "!5"], &[("a b", true)]);
+        assert_eq!(0, stats.lines_with_empty_choice);
+    }
+
+    #[test]
+    fn no_lines() {
+        let stats = stats(vec!["choose", "0"], &[]);
+        assert_eq!(None, stats.min_fields);
+        assert_eq!(0.0, stats.average_fields());
+        assert!(print(&stats, StatsFormat::Text).contains("fields per line: min 0, max 0, average 0.00\n"));
+    }
+
+    #[test]
+    fn print_text() {
+        let mut stats = stats(vec!["choose", "0"], &[("a b c", true), ("a", true)]);
+        stats.bytes_out = 4;
+        let text = print(&stats, StatsFormat::Text);
+        assert!(text.starts_with("lines read: 2\nlines emitted: 2\nlines with an empty choice: 0\n"));
+        assert!(text.contains("fields per line: min 1, max 3, average 2.00\nbytes in: 8\nbytes out: 4\n"));
+        assert!(text.ends_with("elapsed: 2.000s\nthroughput: 1 lines/s, 0.00 MB/s\n"));
+    }
+
+    #[test]
+    fn print_json() {
+        let mut stats = stats(vec!["choose", "0"], &[("a b c", true), ("a", true)]);
+        stats.bytes_out = 4;
+        assert_eq!(
+            concat!(
+                "{\"lines_read\":2,\"lines_emitted\":2,\"lines_with_empty_choice\":0,",
+                "\"fields_min\":1,\"fields_max\":3,\"fields_average\":2,",
+                "\"bytes_in\":8,\"bytes_out\":4,\"elapsed_seconds\":2,",
+                "\"lines_per_second\":1,\"bytes_per_second\":4}\n"
+            ),
+            print(&stats, StatsFormat::Json)
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use std::str::FromStr;
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::result::Result;
+
+/// Strictness is what `--strict` does when a choice cannot be satisfied by a
+/// line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Strictness {
+    /// Report the choice and stop with an error
+    Fail,
+    /// Report the choice and carry on
+    Warn,
+}
+
+impl FromStr for Strictness {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "fail" => Ok(Strictness::Fail),
+            "warn" => Ok(Strictness::Warn),
+            _ => Err(format!("invalid strictness: {}", s)),
+        }
+    }
+}
+
+impl Strictness {
+    pub const VARIANTS: &'static [&'static str] = &["fail", "warn"];
+}
+
+/// Check that every choice can be satisfied by a line, i.e. that no index is
+/// beyond the fields of the line and no range is empty
+///
+/// Each choice that cannot be satisfied is reported on stderr with the line
+/// number and the number of fields in the line. With Strictness::Fail the
+/// first such choice is returned as an error instead.
+pub fn check_line(config: &Config, line: &str, line_number: usize, strictness: Strictness) -> Result<()> {
+    let fields = config.fields(line);
+
+    for choice in config.choices().filter(|c| !c.excluded) {
+        if choice.is_satisfied_by(&fields, config)? {
+            continue;
+        }
+
+        let message = format!(
+            "line {}: choice {} cannot be satisfied by {} field{}",
+            line_number,
+            choice.source,
+            fields.len(),
+            if fields.len() == 1 { "" } else { "s" }
+        );
+
+        match strictness {
+            Strictness::Fail => return Err(Error::Strict(message)),
+            Strictness::Warn => eprintln!("warning: {}", message),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use crate::choice::Choice;
+use crate::config::Config;
+use crate::predicate;
+use crate::result::Result;
+use crate::writer::{WriteReceiver, Writer};
+
+/// Template is an output template given with `--format`, such as
+/// `{0}@{2}:{-1}`, made of literal text and placeholders holding choices
+#[derive(Debug)]
+pub struct Template {
+    pub parts: Vec<Part>,
+}
+
+#[derive(Debug)]
+pub enum Part {
+    Literal(String),
+    Placeholder(Vec<Choice>, Option<FormatSpec>),
+}
+
+/// FormatSpec is the subset of Rust's `format!` spec supported in templates:
+/// `[[fill]align][+][0][width][.precision]`
+#[derive(Debug, Default, PartialEq)]
+pub struct FormatSpec {
+    pub fill: Option<char>,
+    pub align: Option<Align>,
+    pub plus: bool,
+    pub zero: bool,
+    pub width: Option<usize>,
+    pub precision: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl Template {
+    pub fn choices(&self) -> impl Iterator<Item = &Choice> {
+        self.parts.iter().flat_map(|part| match part {
+            Part::Literal(_) => [].iter(),
+            Part::Placeholder(choices, _) => choices.iter(),
+        })
+    }
+
+    pub fn choices_mut(&mut self) -> impl Iterator<Item = &mut Choice> {
+        self.parts.iter_mut().flat_map(|part| match part {
+            Part::Literal(_) => [].iter_mut(),
+            Part::Placeholder(choices, _) => choices.iter_mut(),
+        })
+    }
+
+    /// Print a line through the template
+    ///
+    /// The fields chosen by each placeholder are joined with the output
+    /// separator before the placeholder's spec is applied to them.
+    pub fn print_line<WR: WriteReceiver>(
+        &self,
+        handle: &mut Writer<WR>,
+        config: &Config,
+        line: &str,
+    ) -> Result<()> {
+        let fields = config.fields(line);
+
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => handle.write_literal(text)?,
+                Part::Placeholder(choices, spec) => {
+                    let mut out = Writer::from(Vec::new());
+                    for choice in choices {
+                        choice.print_choice_fields(&fields, config, &mut out)?;
+                    }
+                    let value = String::from_utf8_lossy(&out.into_inner()).into_owned();
+                    match spec {
+                        Some(spec) => handle.write_literal(&spec.apply(&value))?,
+                        None => handle.write_literal(&value)?,
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FormatSpec {
+    /// Format a value according to the spec
+    ///
+    /// Values that are finite decimal numbers are right-aligned by default,
+    /// have precision applied as decimal places, and can be padded with zeros
+    /// after their sign, which like `format!` overrides fill and alignment.
+    /// Other values, including `inf` and `nan`, are left-aligned and truncated
+    /// by precision.
+    pub fn apply(&self, value: &str) -> String {
+        let number = predicate::number(value.trim());
+
+        let mut value = match (number, self.precision) {
+            (Some(n), Some(p)) => format!("{:.*}", p, n),
+            (None, Some(p)) => value.chars().take(p).collect(),
+            (_, None) => value.to_string(),
+        };
+
+        if number.is_some() && self.plus && !value.starts_with('-') {
+            value.insert(0, '+');
+        }
+
+        let len = value.chars().count();
+        let width = match self.width {
+            Some(width) if width > len => width,
+            _ => return value,
+        };
+
+        if number.is_some() && self.zero {
+            let sign = match value.chars().next() {
+                Some('+' | '-') => value.remove(0).to_string(),
+                _ => String::new(),
+            };
+            return format!("{}{}{}", sign, "0".repeat(width - len), value);
+        }
+
+        let align = match (self.align, number) {
+            (Some(align), _) => align,
+            (None, Some(_)) => Align::Right,
+            (None, None) => Align::Left,
+        };
+        let fill = self.fill.unwrap_or(' ').to_string();
+        let (before, after) = match align {
+            Align::Left => (0, width - len),
+            Align::Center => ((width - len) / 2, width - len - (width - len) / 2),
+            Align::Right => (width - len, 0),
+        };
+
+        format!("{}{}{}", fill.repeat(before), value, fill.repeat(after))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spec(width: usize) -> FormatSpec {
+        FormatSpec {
+            width: Some(width),
+            ..FormatSpec::default()
+        }
+    }
+
+    #[test]
+    fn apply_width() {
+        assert_eq!("ab   ", spec(5).apply("ab"));
+        assert_eq!("   12", spec(5).apply("12"));
+        assert_eq!("abcdef", spec(5).apply("abcdef"));
+    }
+
+    #[test]
+    fn apply_align_and_fill() {
+        let align = |fill, align| FormatSpec {
+            fill,
+            align: Some(align),
+            ..spec(6)
+        };
+        assert_eq!("  ab  ", align(None, Align::Center).apply("ab"));
+        assert_eq!(" abc  ", align(None, Align::Center).apply("abc"));
+        assert_eq!("12****", align(Some('*'), Align::Left).apply("12"));
+        assert_eq!("----ab", align(Some('-'), Align::Right).apply("ab"));
+    }
+
+    #[test]
+    fn apply_precision() {
+        let precision = |p| FormatSpec {
+            precision: Some(p),
+            ..FormatSpec::default()
+        };
+        assert_eq!("3.14", precision(2).apply("3.14159"));
+        assert_eq!("2.000", precision(3).apply("2"));
+        assert_eq!("ab", precision(2).apply("abc"));
+    }
+
+    #[test]
+    fn apply_zero_padding() {
+        let zero = FormatSpec { zero: true, ..spec(5) };
+        assert_eq!("00042", zero.apply("42"));
+        assert_eq!("-0042", zero.apply("-42"));
+        assert_eq!("ab   ", zero.apply("ab"));
+    }
+
+    #[test]
+    fn apply_zero_padding_overrides_align() {
+        let zero = |align| FormatSpec {
+            fill: Some('*'),
+            align: Some(align),
+            zero: true,
+            ..spec(8)
+        };
+        assert_eq!("00000042", zero(Align::Right).apply("42"));
+        assert_eq!("-0000042", zero(Align::Left).apply("-42"));
+        assert_eq!("ab******", zero(Align::Left).apply("ab"));
+    }
+
+    #[test]
+    fn apply_non_finite_as_text() {
+        let precision = FormatSpec {
+            precision: Some(1),
+            ..spec(5)
+        };
+        assert_eq!("n    ", precision.apply("nan"));
+        assert_eq!("I    ", precision.apply("Infinity"));
+    }
+
+    #[test]
+    fn apply_plus() {
+        let plus = FormatSpec {
+            plus: true,
+            ..FormatSpec::default()
+        };
+        assert_eq!("+42", plus.apply("42"));
+        assert_eq!("-42", plus.apply("-42"));
+        assert_eq!("ab", plus.apply("ab"));
+    }
+}
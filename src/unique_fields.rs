@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+use std::iter;
+use std::str::FromStr;
+
+use crate::choice::Choice;
+use crate::config::Config;
+use crate::result::Result;
+use crate::writer::{WriteReceiver, Writer};
+
+/// FieldOrder is the order in which fields chosen with `--unique-fields` are
+/// printed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldOrder {
+    /// Print fields in the order their choices first mention them
+    First,
+    /// Print fields in ascending order of their index in the line
+    Ascending,
+}
+
+impl FromStr for FieldOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "first" => Ok(FieldOrder::First),
+            "ascending" => Ok(FieldOrder::Ascending),
+            _ => Err(format!("invalid field order: {}", s)),
+        }
+    }
+}
+
+impl FieldOrder {
+    pub const VARIANTS: &'static [&'static str] = &["first", "ascending"];
+}
+
+/// Print the choices for a line so that each field is printed at most once
+///
+/// Every choice is resolved against the number of fields in the line, and a
+/// field chosen by more than one choice is only printed for the first of them.
+/// Fields covered by excluded choices are never printed. A nested choice
+/// chooses from within a field rather than the field itself, so it does not
+/// stop later choices from printing that field. With `--fill`, a missing field
+/// is filled once, and is ordered by its position beyond the line.
+pub fn print_line<WR: WriteReceiver>(
+    handle: &mut Writer<WR>,
+    config: &Config,
+    line: &str,
+    order: FieldOrder,
+) -> Result<()> {
+    let fields = config.fields(line);
+
+    let mut seen = vec![false; fields.len()];
+    for choice in config.opt.choices.iter().filter(|c| c.excluded) {
+        for i in choice.resolve(&fields)? {
+            seen[i] = true;
+        }
+    }
+
+    // positions missing from the line that have already been filled
+    let mut filled = HashSet::new();
+
+    let mut chosen: Vec<(isize, Option<usize>, &Choice)> = Vec::new();
+    for choice in config.opt.choices.iter().filter(|c| !c.excluded) {
+        let positions: Vec<(isize, Option<usize>)> = match choice.fill(config) {
+            Some(_) => choice.filled_positions(fields.len())?.collect(),
+            None => choice.resolve(&fields)?.into_iter().map(|i| (i as isize, Some(i))).collect(),
+        };
+        for (position, i) in positions {
+            let unseen = match i {
+                Some(i) => !seen[i],
+                None => !filled.contains(&position),
+            };
+            if !unseen {
+                continue;
+            }
+            if choice.nested.is_none() {
+                match i {
+                    Some(i) => seen[i] = true,
+                    None => {
+                        filled.insert(position);
+                    }
+                }
+            }
+            chosen.push((position, i, choice));
+        }
+    }
+
+    if order == FieldOrder::Ascending {
+        chosen.sort_by_key(|(position, _, _)| *position);
+    }
+
+    for (_, i, choice) in chosen {
+        match (i, choice.fill(config)) {
+            (Some(i), _) => choice.print_fields_at(iter::once(i), &fields, config, handle)?,
+            (None, Some(fill)) => handle.write_choice_separable(fill, config)?,
+            (None, None) => (),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_order() {
+        assert_eq!(FieldOrder::First, "first".parse().unwrap());
+        assert_eq!(FieldOrder::Ascending, "ascending".parse().unwrap());
+        assert!("descending".parse::<FieldOrder>().is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use std::io::{self, Write};
+
+use crate::choice::Choice;
+use crate::config::Config;
+use crate::result::Result;
+
+/// UnusedChoices tracks, for `--warn-unused`, which choices have produced a
+/// field from any line of the input
+#[derive(Debug)]
+pub struct UnusedChoices {
+    /// Whether each choice that is not excluded has produced a field, in the
+    /// order of Config::choices
+    used: Vec<bool>,
+    lines: usize,
+    max_fields: usize,
+}
+
+impl UnusedChoices {
+    pub fn new(config: &Config) -> Self {
+        UnusedChoices {
+            used: vec![false; config.choices().filter(|c| !c.excluded).count()],
+            lines: 0,
+            max_fields: 0,
+        }
+    }
+
+    /// Add a line that the choices are applied to
+    pub fn add_line(&mut self, config: &Config, line: &str) -> Result<()> {
+        let fields = config.fields(line);
+
+        self.lines += 1;
+        self.max_fields = self.max_fields.max(fields.len());
+
+        for (used, choice) in self.used.iter_mut().zip(config.choices().filter(|c| !c.excluded)) {
+            if !*used {
+                *used = choice.produces_field(&fields, config)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The choices that have not produced a field from any line
+    pub fn unused<'a>(&'a self, config: &'a Config) -> impl Iterator<Item = &'a Choice> {
+        config
+            .choices()
+            .filter(|c| !c.excluded)
+            .zip(&self.used)
+            .filter(|(_, used)| !**used)
+            .map(|(choice, _)| choice)
+    }
+
+    /// Print a warning for each choice that has not produced a field from any
+    /// line, with the maximum number of fields in a line
+    pub fn print<W: Write>(&self, out: &mut W, config: &Config) -> io::Result<()> {
+        for choice in self.unused(config) {
+            writeln!(
+                out,
+                "warning: choice {} never produced a field, from {} line{} with at most {} field{}",
+                choice.source,
+                self.lines,
+                if self.lines == 1 { "" } else { "s" },
+                self.max_fields,
+                if self.max_fields == 1 { "" } else { "s" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::opt::Opt;
+    use structopt::StructOpt;
+
+    fn warnings(args: Vec<&str>, lines: &[&str]) -> String {
+        let config = Config::new(Opt::from_iter(args)).unwrap();
+        let mut unused = UnusedChoices::new(&config);
+        for line in lines {
+            unused.add_line(&config, line).unwrap();
+        }
+        let mut out = Vec::new();
+        unused.print(&mut out, &config).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn warn_choice_beyond_fields() {
+        assert_eq!(
+            "warning: choice 7 never produced a field, from 2 lines with at most 6 fields\n",
+            warnings(vec!["choose", "0", "7"], &["a b c d e f", "a b"])
+        );
+    }
+
+    #[test]
+    fn choice_used_by_any_line() {
+        assert_eq!("", warnings(vec!["choose", "0", "3"], &["a b", "a b c d", "a"]));
+    }
+
+    #[test]
+    fn warn_empty_range() {
+        assert_eq!(
+            "warning: choice 2..2 never produced a field, from 1 line with at most 3 fields\n",
+            warnings(vec!["choose", "2..2"], &["a b c"])
+        );
+    }
+
+    #[test]
+    fn warn_each_unused_choice() {
+        let warnings = warnings(vec!["choose", "5", "0", "-9"], &["a"]);
+        assert_eq!(
+            concat!(
+                "warning: choice 5 never produced a field, from 1 line with at most 1 field\n",
+                "warning: choice -9 never produced a field, from 1 line with at most 1 field\n"
+            ),
+            warnings
+        );
+    }
+
+    #[test]
+    fn excluded_choices_are_not_warned() {
+        assert_eq!("", warnings(vec!["choose", "0", "!7"], &["a b"]));
+    }
+
+    #[test]
+    fn warn_template_choices() {
+        assert_eq!(
+            "warning: choice 4 never produced a field, from 1 line with at most 2 fields\n",
+            warnings(vec!["choose", "--format", "{0} {4}"], &["a b"])
+        );
+    }
+
+    #[test]
+    fn warn_nested_choice_beyond_subfields() {
+        assert_eq!(
+            "warning: choice 1/,/3 never produced a field, from 1 line with at most 3 fields\n",
+            warnings(vec!["choose", "1/,/3"], &["a,x b c"])
+        );
+    }
+
+    #[test]
+    fn nested_choice_used_by_any_line() {
+        assert_eq!("", warnings(vec!["choose", "1/,/1"], &["a b", "a b,c"]));
+    }
+
+    #[test]
+    fn warn_subscript_beyond_chars() {
+        assert_eq!(
+            "warning: choice 1[5:] never produced a field, from 2 lines with at most 2 fields\n",
+            warnings(vec!["choose", "1[5:]"], &["a bcd", "a"])
+        );
+    }
+
+    #[test]
+    fn subscript_used_by_any_line() {
+        assert_eq!("", warnings(vec!["choose", "1[5:]"], &["a bcd", "a bcdefg"]));
+    }
+
+    #[test]
+    fn no_lines() {
+        assert_eq!(
+            "warning: choice 0 never produced a field, from 0 lines with at most 0 fields\n",
+            warnings(vec!["choose", "0"], &[])
+        );
+    }
+}
@@ -0,0 +1,691 @@
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+use structopt::clap::ArgSettings;
+
+use crate::error::Error;
+use crate::opt::Opt;
+use crate::preset::Preset;
+use crate::result::Result;
+use crate::spec_file::{self, SpecFile};
+
+/// UserConfig is the defaults for options read from the user's config file
+/// and the `CHOOSE_OPTS` environment variable, and the spec files named on the
+/// command line
+///
+/// The defaults are given to the argument parser before the arguments from
+/// the command line, leaving out any option that is given again later, by its
+/// short or long name: an option given on the command line replaces the same
+/// option in the config file or `CHOOSE_OPTS`, even one that may be repeated
+/// like `--field-separator`, and `CHOOSE_OPTS` replaces the config file. A
+/// default that conflicts with a later option (e.g. `--one-indexed` with
+/// `--cut-syntax`) is dropped. The arguments of a spec
+/// file are part of the command line, where `--spec-file` names it.
+#[derive(Debug, Default)]
+pub struct UserConfig {
+    pub path: Option<PathBuf>,
+    pub presets: Vec<Preset>,
+    file_args: Vec<OsString>,
+    env_args: Vec<OsString>,
+    spec_files: Vec<SpecFile>,
+}
+
+impl UserConfig {
+    /// Load the config file `$XDG_CONFIG_HOME/choose/config.toml` (or
+    /// `~/.config/choose/config.toml`) and `CHOOSE_OPTS`, unless the command
+    /// line has `--no-config`, and the spec files named on the command line
+    pub fn load(cli: &[OsString]) -> Result<Self> {
+        let spec_files = SpecFile::paths(cli)
+            .into_iter()
+            .map(SpecFile::read)
+            .collect::<Result<Vec<_>>>()?;
+
+        let disabled = cli
+            .iter()
+            .skip(1)
+            .take_while(|arg| *arg != "--")
+            .any(|arg| arg == "--no-config");
+        if disabled {
+            return Ok(UserConfig {
+                spec_files,
+                ..UserConfig::default()
+            });
+        }
+
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+        let path = config_home.map(|dir| dir.join("choose").join("config.toml"));
+
+        let mut config = UserConfig::from_sources(path, env::var_os("CHOOSE_OPTS").as_deref())?;
+        config.spec_files = spec_files;
+        Ok(config)
+    }
+
+    /// Read the defaults from a config file, which may not exist, and the
+    /// value of `CHOOSE_OPTS`
+    pub fn from_sources(path: Option<PathBuf>, env_opts: Option<&OsStr>) -> Result<Self> {
+        let mut config = UserConfig::default();
+
+        if let Some(path) = path {
+            match fs::read_to_string(&path) {
+                Ok(src) => {
+                    (config.file_args, config.presets) = read_file(&src)
+                        .map_err(|e| Error::Config(format!("invalid config file {}: {}", path.display(), e)))?;
+                    config.path = Some(path);
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+                Err(e) => return Err(Error::Config(format!("failed to read config file {}: {}", path.display(), e))),
+            }
+        }
+
+        if let Some(opts) = env_opts {
+            let opts = opts
+                .to_str()
+                .ok_or_else(|| Error::Config(String::from("CHOOSE_OPTS is not valid UTF-8")))?;
+            config.env_args = shell_words::split(opts)
+                .map_err(|e| Error::Config(format!("invalid CHOOSE_OPTS: {}", e)))?
+                .into_iter()
+                .map(OsString::from)
+                .collect();
+        }
+
+        Ok(config)
+    }
+
+    /// The default options, in the order they are given to the argument
+    /// parser, leaving out those in the config file that are also in
+    /// `CHOOSE_OPTS`, those that are given on the command line, and those
+    /// that conflict with a later option
+    fn defaults(&self, cli: &[OsString]) -> Vec<GivenOption> {
+        let cli = options(cli.get(1..).unwrap_or_default());
+        let env = options(&self.env_args);
+        let defaults = options(&self.file_args)
+            .into_iter()
+            .filter(|default| !default.overridden_by(&env))
+            .chain(env.iter().cloned())
+            .filter(|default| !default.overridden_by(&cli))
+            .collect::<Vec<_>>();
+
+        // a default gives way to a later option it conflicts with, so that
+        // e.g. --one-indexed in the config file allows --cut-syntax
+        defaults
+            .iter()
+            .enumerate()
+            .filter(|(i, default)| !default.conflicts_with(defaults[i + 1..].iter().chain(&cli)))
+            .map(|(_, default)| default.clone())
+            .collect()
+    }
+
+    /// The arguments to parse the command line with: the program name, the
+    /// defaults, and the rest of the command line, with the arguments of its
+    /// spec files
+    ///
+    /// Arguments that clap rejects are returned as they are, so that parsing
+    /// them reports the error as for any command line.
+    pub fn args(&self, cli: &[OsString]) -> Result<Vec<OsString>> {
+        let cli = spec_file::splice(cli, &self.spec_files);
+        let defaults = self.defaults(&cli);
+
+        let args = cli
+            .iter()
+            .take(1)
+            .chain(defaults.iter().flat_map(|default| &default.args))
+            .chain(cli.iter().skip(1))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if let Ok(matches) = Opt::clap().get_matches_from_safe(&args) {
+            // a key of the config file or a directive of a spec file that is
+            // not an option is taken as a choice, since choices can start with
+            // a hyphen
+            let choices = matches.values_of_os("choices").into_iter().flatten().collect::<Vec<_>>();
+            let is_choice = |arg: &OsString| choices.contains(&arg.as_os_str());
+            if let Some(arg) = self.file_args.iter().find(|arg| is_choice(arg)) {
+                return Err(unknown_option(arg, "config file"));
+            }
+            for spec_file in &self.spec_files {
+                let mut options = spec_file.args.iter().filter(|arg| arg.to_string_lossy().starts_with("--"));
+                if let Some(arg) = options.find(|arg| is_choice(arg)) {
+                    return Err(unknown_option(arg, &format!("spec file {}", spec_file.path.display())));
+                }
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+/// The long names of the options that have a short name, so that a default
+/// given as e.g. `-f :` is overridden by `--field-separator` on the command
+/// line, and the other way around
+const SHORT_NAMES: &[(char, &str)] = &[
+    ('c', "character-wise"),
+    ('d', "debug"),
+    ('f', "field-separator"),
+    ('i', "input"),
+    ('n', "non-greedy"),
+    ('o', "output-field-separator"),
+    ('x', "exclusive"),
+];
+
+/// GivenOption is an option as the arguments it is given in, e.g. `-f :`,
+/// with the long names of the options it sets, which are several for short
+/// flags given together (e.g. `-xn`) and none for an argument that is not an
+/// option, like a choice
+#[derive(Debug, Clone)]
+struct GivenOption {
+    names: Vec<String>,
+    args: Vec<OsString>,
+}
+
+impl GivenOption {
+    /// Whether any option it sets is also set by one of `options`
+    fn overridden_by(&self, options: &[GivenOption]) -> bool {
+        self.names
+            .iter()
+            .any(|name| options.iter().any(|option| option.names.contains(name)))
+    }
+
+    /// Whether any argument it gives cannot be given with one given by
+    /// `options`, e.g. `--one-indexed` with `--cut-syntax`
+    fn conflicts_with<'a>(&self, mut options: impl Iterator<Item = &'a GivenOption>) -> bool {
+        options.any(|option| {
+            self.arg_names()
+                .any(|a| option.arg_names().any(|b| CONFLICTS.contains(&(a, b))))
+        })
+    }
+
+    /// The names of the arguments it gives, where an argument that is not an
+    /// option is a choice
+    fn arg_names(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        match self.names.is_empty() {
+            true => Box::new(std::iter::once("choices")),
+            false => Box::new(self.names.iter().map(String::as_str)),
+        }
+    }
+}
+
+/// Group arguments into the options they give, up to `--`
+fn options(args: &[OsString]) -> Vec<GivenOption> {
+    let mut options = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let text = arg.to_string_lossy();
+        if text == "--" {
+            break;
+        }
+
+        let mut names = Vec::new();
+        let mut needs_value = false;
+        if let Some(long) = text.strip_prefix("--") {
+            let name = long.split('=').next().unwrap_or(long);
+            needs_value = !long.contains('=') && takes_value(name);
+            names.push(name.to_string());
+        } else if let Some(shorts) = text.strip_prefix('-') {
+            for (i, c) in shorts.char_indices() {
+                let name = match SHORT_NAMES.iter().find(|(short, _)| *short == c) {
+                    Some((_, name)) => *name,
+                    None => break,
+                };
+                names.push(name.to_string());
+                if takes_value(name) {
+                    // the rest of the argument is the value, if there is any
+                    needs_value = i + c.len_utf8() == shorts.len();
+                    break;
+                }
+            }
+        }
+
+        let mut given = vec![arg.clone()];
+        if needs_value {
+            given.extend(args.next().cloned());
+        }
+        options.push(GivenOption { names, args: given });
+    }
+
+    options
+}
+
+lazy_static! {
+    /// The long names of the options that take a value in the next argument,
+    /// e.g. `field-separator` but not `stats`, whose value must follow `=`
+    static ref VALUED_OPTIONS: Vec<&'static str> = Opt::clap()
+        .p
+        .opts
+        .iter()
+        .filter(|opt| !opt.b.settings.is_set(ArgSettings::RequireEquals))
+        .filter_map(|opt| opt.s.long)
+        .collect();
+
+    /// The pairs of arguments that cannot be given together, both ways round,
+    /// by their names, which are the long names of options or `choices`
+    static ref CONFLICTS: Vec<(&'static str, &'static str)> = {
+        let app = Opt::clap();
+        let args = app
+            .p
+            .flags
+            .iter()
+            .map(|arg| &arg.b)
+            .chain(app.p.opts.iter().map(|arg| &arg.b))
+            .chain(app.p.positionals.values().map(|arg| &arg.b));
+        args.flat_map(|arg| {
+            let blacklist = arg.blacklist.iter().flatten();
+            blacklist.flat_map(move |other| [(arg.name, *other), (*other, arg.name)])
+        })
+        .collect()
+    };
+}
+
+/// Whether the option with a long name takes a value, e.g. `field-separator`
+fn takes_value(name: &str) -> bool {
+    VALUED_OPTIONS.contains(&name)
+}
+
+/// The error for an argument from a file that is not an option
+fn unknown_option(arg: &OsStr, file: &str) -> Error {
+    Error::Config(format!("unknown option '{}' in {}", arg.to_string_lossy(), file))
+}
+
+/// Read a config file into arguments and presets, where each key is the long
+/// name of an option, e.g.
+///
+/// ```toml
+/// one-indexed = true
+/// output-field-separator = ","
+/// where = ["0=~^GET", "-1!=200"]
+///
+/// [presets.access]
+/// pattern = '^(\S+) \S+ \S+ \[([^]]*)\]'
+/// fields = { host = 0, time = 1 }
+/// ```
+///
+/// becomes `--one-indexed --output-field-separator=, --where=0=~^GET
+/// --where=-1!=200` and the preset `access`. A flag set to false is left out.
+fn read_file(src: &str) -> std::result::Result<(Vec<OsString>, Vec<Preset>), String> {
+    let table = match src.parse::<toml::Value>().map_err(|e| e.to_string())? {
+        toml::Value::Table(table) => table,
+        _ => return Err(String::from("expected a table")),
+    };
+
+    let mut args = Vec::new();
+    let mut presets = Vec::new();
+    for (key, value) in &table {
+        let values = match value {
+            toml::Value::Table(table) if key == "presets" => {
+                for (name, preset) in table {
+                    presets.push(Preset::from_toml(name, preset)?);
+                }
+                continue;
+            }
+            toml::Value::Boolean(true) => {
+                args.push(OsString::from(format!("--{}", key)));
+                continue;
+            }
+            toml::Value::Boolean(false) => continue,
+            toml::Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+
+        for value in values {
+            let value = match value {
+                toml::Value::String(s) => s.clone(),
+                toml::Value::Integer(i) => i.to_string(),
+                toml::Value::Float(f) => f.to_string(),
+                _ => return Err(format!("{} must be a boolean, string, number, or array of them", key)),
+            };
+            args.push(OsString::from(format!("--{}={}", key, value)));
+        }
+    }
+    Ok((args, presets))
+}
+
+/// Show where the defaults come from and the arguments they give, e.g.
+///
+/// ```text
+/// config file: /home/user/.config/choose/config.toml
+///     --one-indexed --output-field-separator=,
+/// CHOOSE_OPTS:
+///     -f :
+/// ```
+impl fmt::Display for UserConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let join = |args: &[OsString]| shell_words::join(args.iter().map(|arg| arg.to_string_lossy()));
+
+        match &self.path {
+            Some(path) => writeln!(f, "config file: {}\n    {}", path.display(), join(&self.file_args))?,
+            None => writeln!(f, "config file: none")?,
+        }
+        if !self.presets.is_empty() {
+            let names = self.presets.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+            writeln!(f, "presets: {}", names.join(", "))?;
+        }
+        match self.env_args.as_slice() {
+            [] => writeln!(f, "CHOOSE_OPTS: none")?,
+            args => writeln!(f, "CHOOSE_OPTS:\n    {}", join(args))?,
+        }
+        for spec_file in &self.spec_files {
+            writeln!(f, "spec file: {}\n    {}", spec_file.path.display(), join(&spec_file.args))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use structopt::clap::{self, ErrorKind};
+
+    impl UserConfig {
+        fn try_parse(&self, cli: &[OsString]) -> clap::Result<Opt> {
+            Opt::from_iter_safe(self.args(cli).unwrap())
+        }
+    }
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    fn config(file: &[&str], env: &[&str]) -> UserConfig {
+        UserConfig {
+            path: None,
+            presets: Vec::new(),
+            file_args: args(file),
+            env_args: args(env),
+            spec_files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn file_args_from_toml() {
+        let src = "one-indexed = true\nexclusive = false\noutput-field-separator = \",\"\nwhere = [\"0=a\", \"-1!=2\"]\n";
+        assert_eq!(
+            args(&["--one-indexed", "--output-field-separator=,", "--where=0=a", "--where=-1!=2"]),
+            read_file(src).unwrap().0
+        );
+    }
+
+    #[test]
+    fn file_args_number() {
+        assert_eq!(args(&["--escape=1"]), read_file("escape = 1").unwrap().0);
+    }
+
+    #[test]
+    fn file_args_invalid() {
+        assert!(read_file("one-indexed = ").is_err());
+        assert!(read_file("[table]\nkey = true").is_err());
+        assert!(read_file("[presets.p]\nkey = true").is_err());
+    }
+
+    #[test]
+    fn read_presets() {
+        let (args, presets) = read_file("preset = \"users\"\n[presets.users]\nfield-separator = \":\"\n").unwrap();
+        assert_eq!(vec![OsString::from("--preset=users")], args);
+        assert_eq!(vec![String::from("users")], presets.iter().map(|p| p.name.clone()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn env_args_split_like_a_shell() {
+        let config = UserConfig::from_sources(None, Some(OsStr::new("-o ', ' --one-indexed"))).unwrap();
+        assert_eq!(args(&["-o", ", ", "--one-indexed"]), config.env_args);
+    }
+
+    #[test]
+    fn missing_file_is_not_an_error() {
+        let path = env::temp_dir().join("choose-missing-config-test").join("config.toml");
+        let config = UserConfig::from_sources(Some(path), None).unwrap();
+        assert_eq!(None, config.path);
+    }
+
+    #[test]
+    fn read_config_file() {
+        let dir = env::temp_dir().join(format!("choose-config-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, "one-indexed = true\n").unwrap();
+        let config = UserConfig::from_sources(Some(path.clone()), None).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!((Some(path), args(&["--one-indexed"])), (config.path, config.file_args));
+    }
+
+    #[test]
+    fn no_config() {
+        let config = UserConfig::load(&args(&["choose", "--no-config", "0"])).unwrap();
+        assert!(config.defaults(&args(&["choose", "0"])).is_empty());
+    }
+
+    #[test]
+    fn defaults_apply() {
+        let opt = config(&["--one-indexed", "--output-field-separator=,"], &[])
+            .try_parse(&args(&["choose", "1"]))
+            .unwrap();
+        assert!(opt.one_indexed);
+        assert_eq!(Some(String::from(",")), opt.output_field_separator);
+    }
+
+    #[test]
+    fn command_line_overrides_defaults() {
+        let opt = config(&["--output-field-separator=,"], &["-o", ":"])
+            .try_parse(&args(&["choose", "-o", ";", "1"]))
+            .unwrap();
+        assert_eq!(Some(String::from(";")), opt.output_field_separator);
+    }
+
+    #[test]
+    fn env_overrides_file() {
+        let opt = config(&["--output-field-separator=,"], &["-o", ":"])
+            .try_parse(&args(&["choose", "1"]))
+            .unwrap();
+        assert_eq!(Some(String::from(":")), opt.output_field_separator);
+    }
+
+    #[test]
+    fn conflicting_default_gives_way() {
+        let opt = config(&["--one-indexed"], &[])
+            .try_parse(&args(&["choose", "--cut-syntax", "1"]))
+            .unwrap();
+        assert!(opt.cut_syntax && !opt.one_indexed);
+    }
+
+    #[test]
+    fn conflicting_default_with_value_gives_way() {
+        let opt = config(&[], &["--field-separator", ":"])
+            .try_parse(&args(&["choose", "--separators", ",", "1"]))
+            .unwrap();
+        assert!(opt.field_separator.is_empty());
+        assert_eq!(Some(String::from(",")), opt.separators);
+    }
+
+    #[test]
+    fn default_conflicting_with_choices_gives_way() {
+        let opt = config(&["--describe"], &[]).try_parse(&args(&["choose", "1"])).unwrap();
+        assert!(!opt.describe);
+        assert_eq!(vec![String::from("1")], opt.choice_args);
+    }
+
+    #[test]
+    fn file_default_conflicting_with_env_default_gives_way() {
+        let opt = config(&["--one-indexed"], &["--cut-syntax"])
+            .try_parse(&args(&["choose", "1"]))
+            .unwrap();
+        assert!(opt.cut_syntax && !opt.one_indexed);
+    }
+
+    #[test]
+    fn conflict_on_command_line_is_an_error() {
+        let e = config(&[], &[])
+            .try_parse(&args(&["choose", "--cut-syntax", "--one-indexed", "1"]))
+            .unwrap_err();
+        assert_eq!(ErrorKind::ArgumentConflict, e.kind);
+    }
+
+    #[test]
+    fn repeated_options_replace_defaults() {
+        let opt = config(&["--where=0=a", "--where=0=b"], &[])
+            .try_parse(&args(&["choose", "--where", "1=b", "0"]))
+            .unwrap();
+        assert_eq!(1, opt.predicates.len());
+    }
+
+    #[test]
+    fn repeated_options_add_to_each_other() {
+        let opt = config(&["--where=0=a", "--where=0=b"], &[])
+            .try_parse(&args(&["choose", "0"]))
+            .unwrap();
+        assert_eq!(2, opt.predicates.len());
+    }
+
+    #[test]
+    fn command_line_field_separator_replaces_default() {
+        let opt = config(&["--field-separator=:"], &[])
+            .try_parse(&args(&["choose", "-f", ",", "1"]))
+            .unwrap();
+        assert_eq!(vec![String::from(",")], opt.field_separator);
+    }
+
+    #[test]
+    fn short_default_replaced_by_long_option() {
+        let opt = config(&[], &["-f", ":", "-o:"])
+            .try_parse(&args(&["choose", "--field-separator=,", "--output-field-separator", ";", "1"]))
+            .unwrap();
+        assert_eq!(vec![String::from(",")], opt.field_separator);
+        assert_eq!(Some(String::from(";")), opt.output_field_separator);
+    }
+
+    #[test]
+    fn env_field_separator_replaces_file() {
+        let opt = config(&["--field-separator=:"], &["-f", ","])
+            .try_parse(&args(&["choose", "1"]))
+            .unwrap();
+        assert_eq!(vec![String::from(",")], opt.field_separator);
+    }
+
+    #[test]
+    fn short_conflicting_default_gives_way() {
+        let opt = config(&[], &["-f", ":"])
+            .try_parse(&args(&["choose", "--separators", ",", "1"]))
+            .unwrap();
+        assert!(opt.field_separator.is_empty());
+        assert_eq!(Some(String::from(",")), opt.separators);
+    }
+
+    #[test]
+    fn short_flags_given_together() {
+        let opt = config(&[], &["-xn"])
+            .try_parse(&args(&["choose", "--cut-syntax", "1"]))
+            .unwrap();
+        assert!(opt.cut_syntax && !opt.exclusive && !opt.non_greedy);
+    }
+
+    #[test]
+    fn group_options() {
+        let given = options(&args(&["-f", ":", "-o,", "--one-indexed", "--fill", "-", "--where=0=a", "-xn", "-1", "--", "-f"]));
+        let names = given.iter().map(|option| option.names.join(" ")).collect::<Vec<_>>();
+        assert_eq!(
+            vec!["field-separator", "output-field-separator", "one-indexed", "fill", "where", "exclusive non-greedy", ""],
+            names
+        );
+        assert_eq!(args(&["--fill", "-"]), given[3].args);
+    }
+
+    #[test]
+    fn options_take_values() {
+        assert!(takes_value("field-separator"));
+        assert!(takes_value("input"));
+        assert!(!takes_value("one-indexed"));
+        assert!(!takes_value("stats"));
+    }
+
+    #[test]
+    fn short_names_match_options() {
+        for (short, name) in SHORT_NAMES {
+            let mut cli = vec![String::from("choose"), String::from("--print-config"), format!("-{}", short)];
+            if takes_value(name) {
+                cli.push(String::from(":"));
+            }
+            let matches = Opt::clap().get_matches_from_safe(&cli).unwrap();
+            assert!(matches.is_present(name), "-{} is not --{}", short, name);
+        }
+    }
+
+    #[test]
+    fn short_names_are_complete() {
+        let named = |c: char| SHORT_NAMES.iter().any(|(short, _)| *short == c) || c == 'h' || c == 'V';
+        for c in ('a'..='z').chain('A'..='Z').filter(|c| !named(*c)) {
+            let arg = format!("-{}", c);
+            let matches = Opt::clap().get_matches_from_safe(["choose", arg.as_str()]).unwrap();
+            assert_eq!(Some(arg.as_str()), matches.value_of("choices"), "-{} is an option", c);
+        }
+    }
+
+    #[test]
+    fn unknown_file_key() {
+        match config(&["--bogus"], &[]).args(&args(&["choose", "1"])) {
+            Err(Error::Config(e)) => assert_eq!("unknown option '--bogus' in config file", e),
+            r => panic!("expected a config error, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn choice_starting_with_hyphen_is_not_a_file_key() {
+        let opt = config(&["--one-indexed"], &[]).try_parse(&args(&["choose", "-1"])).unwrap();
+        assert_eq!(vec![String::from("-1")], opt.choice_args);
+    }
+
+    fn with_spec_file(spec_args: &[&str]) -> UserConfig {
+        UserConfig {
+            spec_files: vec![SpecFile {
+                path: PathBuf::from("users.choose"),
+                args: args(spec_args),
+            }],
+            ..config(&["--output-field-separator=,"], &[])
+        }
+    }
+
+    #[test]
+    fn spec_file_overrides_defaults() {
+        let opt = with_spec_file(&["--output-field-separator=:", "0"])
+            .try_parse(&args(&["choose", "--spec-file", "users.choose"]))
+            .unwrap();
+        assert_eq!(Some(String::from(":")), opt.output_field_separator);
+        assert_eq!(vec![String::from("0")], opt.choice_args);
+    }
+
+    #[test]
+    fn command_line_overrides_spec_file() {
+        let opt = with_spec_file(&["--output-field-separator=:", "0"])
+            .try_parse(&args(&["choose", "--spec-file", "users.choose", "-o", ";", "2"]))
+            .unwrap();
+        assert_eq!(Some(String::from(";")), opt.output_field_separator);
+        assert_eq!(vec![String::from("0"), String::from("2")], opt.choice_args);
+    }
+
+    #[test]
+    fn unknown_spec_file_directive() {
+        match with_spec_file(&["--bogus", "0"]).args(&args(&["choose", "--spec-file=users.choose"])) {
+            Err(Error::Config(e)) => assert_eq!("unknown option '--bogus' in spec file users.choose", e),
+            r => panic!("expected a config error, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn display() {
+        let config = UserConfig {
+            path: Some(PathBuf::from("/home/user/.config/choose/config.toml")),
+            presets: Vec::new(),
+            file_args: args(&["--one-indexed", "--output-field-separator=, "]),
+            env_args: Vec::new(),
+            spec_files: Vec::new(),
+        };
+        assert_eq!(
+            "config file: /home/user/.config/choose/config.toml\n    --one-indexed '--output-field-separator=, '\nCHOOSE_OPTS: none\n",
+            config.to_string()
+        );
+    }
+}
@@ -4,6 +4,7 @@ use std::fmt::Debug;
 pub trait Writeable: Copy + Debug {
     fn as_bytes(&self) -> Cow<'_, [u8]>;
     fn is_empty(&self) -> bool;
+    fn len(&self) -> usize;
 }
 
 impl Writeable for &str {
@@ -14,6 +15,10 @@ impl Writeable for &str {
     fn is_empty(&self) -> bool {
         str::is_empty(self)
     }
+
+    fn len(&self) -> usize {
+        str::len(self)
+    }
 }
 
 impl Writeable for char {
@@ -26,4 +31,8 @@ impl Writeable for char {
     fn is_empty(&self) -> bool {
         false
     }
+
+    fn len(&self) -> usize {
+        self.len_utf8()
+    }
 }
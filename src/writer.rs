@@ -6,37 +6,22 @@ use crate::writeable::Writeable;
 
 pub struct Writer<WR: WriteReceiver> {
     first_of_line: RefCell<bool>,
+    bytes_written: usize,
     pub inner: Box<WR>,
 }
 
 pub trait WriteReceiver: Write {
-    /// write_choice prints the Writable, followed by the output separator (if
-    /// print_separator is true)
-    ///
-    /// This means the caller must know if there will be another Writeable on
-    /// the current line. To avoid the need to look forward, use
-    /// write_choice_separable
-    fn write_choice<Wa: Writeable>(
-        &mut self,
-        b: Wa,
-        config: &Config,
-        print_separator: bool,
-    ) -> io::Result<()> {
-        let num_bytes_written = self.write(&b.as_bytes())?;
-        if num_bytes_written > 0 && print_separator {
-            self.write_separator(config)?;
-        };
-        Ok(())
-    }
-
     /// write_choice_separable prints the output separator (if first is not
     /// true) followed by the Writeable
     ///
-    /// when looping through a series of Writeables, this achieves the same
-    /// things as write_choice, but requires the caller to track `first`. This
-    /// additional overhead on the caller allows for performance gains by
-    /// removing look-ahead capabilities on the caller side.
-    /// 
+    /// This requires the caller to track `first`, rather than to know if there
+    /// will be another Writeable on the current line. This additional overhead
+    /// on the caller allows for performance gains by removing look-ahead
+    /// capabilities on the caller side.
+    ///
+    /// The separator is printed even if the Writeable is empty, so that empty
+    /// fields kept by the empty field policy are separated like any other.
+    ///
     /// [Writer] is provided as a convenience for tracking `first`.
     fn write_choice_separable<Wa: Writeable>(
         &mut self,
@@ -44,10 +29,10 @@ pub trait WriteReceiver: Write {
         config: &Config,
         first: bool,
     ) -> io::Result<()> {
-        if !first && !b.is_empty() {
+        if !first {
             self.write_separator(config)?;
         }
-        self.write(&b.as_bytes())?;
+        self.write_all(&b.as_bytes())?;
         Ok(())
     }
 
@@ -60,46 +45,53 @@ impl<W: Write> WriteReceiver for BufWriter<W> {}
 
 impl<W: Write> WriteReceiver for LineWriter<W> {}
 
+impl WriteReceiver for Vec<u8> {}
+
 impl<WR: WriteReceiver> From<WR> for Writer<WR> {
     fn from(wr: WR) -> Self {
         Self {
             first_of_line: RefCell::from(true),
+            bytes_written: 0,
             inner: Box::from(wr),
         }
     }
 }
 
 impl<WR: WriteReceiver> Writer<WR> {
-    pub fn write_choice<Wa: Writeable>(
+    pub fn write_choice_separable<Wa: Writeable>(
         &mut self,
         b: Wa,
         config: &Config,
-        print_separator: bool,
     ) -> io::Result<()> {
-        WR::write_choice(&mut self.inner, b, config, print_separator)?;
+        let first = *self.first_of_line.borrow();
+        WR::write_choice_separable(&mut self.inner, b, config, first)?;
+        self.bytes_written += b.len() + if first { 0 } else { config.output_separator.len() };
         self.first_of_line.replace(false);
         Ok(())
     }
 
-    pub fn write_choice_separable<Wa: Writeable>(
-        &mut self,
-        b: Wa,
-        config: &Config,
-    ) -> io::Result<()> {
-        WR::write_choice_separable(&mut self.inner, b, config, *self.first_of_line.borrow())?;
+    /// write_literal prints text as-is, without an output separator
+    pub fn write_literal(&mut self, s: &str) -> io::Result<()> {
+        self.inner.write_all(s.as_bytes())?;
+        self.bytes_written += s.len();
         self.first_of_line.replace(false);
         Ok(())
     }
 
     pub fn write_line(&mut self) -> io::Result<()> {
         WR::write(&mut self.inner, b"\n").map(|_| ())?;
+        self.bytes_written += 1;
         self.first_of_line.replace(true);
         Ok(())
     }
 
-    /// into_inner decomposes a Writer into its underlying WriteReceiver,
-    /// convenient for testing
-    #[allow(unused)]
+    /// bytes_written is the number of bytes written so far, including output
+    /// separators and newlines
+    pub fn bytes_written(&self) -> usize {
+        self.bytes_written
+    }
+
+    /// into_inner decomposes a Writer into its underlying WriteReceiver
     pub fn into_inner(self) -> WR {
         *self.inner
     }
@@ -5,6 +5,11 @@ test_dir="test"
 orig_dir="$(pwd)"
 cd "$(git rev-parse --show-toplevel)"
 
+# keep the user's defaults out of the tests
+config_home="$(mktemp -d)"
+export XDG_CONFIG_HOME="$config_home"
+unset CHOOSE_OPTS
+
 # basic functionality
 diff -w <(cargo run -- 0:1 -i ${test_dir}/lorem.txt 2>/dev/null) <(cat "${test_dir}/choose_0x1.txt")
 diff -w <(cargo run -- 0 3 -i ${test_dir}/lorem.txt 2>/dev/null) <(cat "${test_dir}/choose_0_3.txt")
@@ -28,6 +33,45 @@ diff -w <(cargo run -- 1:-3 -i ${test_dir}/alphabet.txt 2>/dev/null) <(cat "${te
 diff -w <(cargo run -- -f : 1 -i ${test_dir}/colons.txt 2>/dev/null) <(cat "${test_dir}/choose_colon_1.txt")
 diff -w <(echo a:b | cargo run -- -f : 1) <(echo b)
 diff -w <(echo -n a:b | cargo run -- -f : 1) <(echo b)
+diff -w <(echo 'a\|b|c' | cargo run -- -f '\|' --escape '\' --unescape 0) <(echo 'a|b')
+diff -w <(echo 'GET / host:user=alice:port=80' | cargo run -- 2/:/1/=/-1) <(echo alice)
+diff -w <(echo 'a,,c,d' | cargo run -- -f , -o , --empty-fields keep 0:2) <(echo 'a,,c' | cut -d, -f1-3)
+diff -w <(echo 'a,b;c|d' | cargo run -- --separators ',;|' 1:2) <(echo 'b c')
+diff -w <(echo 'a,b;c|d' | cargo run -- -f , -f ';' 1:2) <(echo 'b c|d')
+diff -w <(echo 'a b c d e f' | cargo run -- ::-2) <(echo 'f d b')
+diff -w <(printf 'a\tb\tc\td\te\tf\tg\n' | cargo run -- --cut-syntax -f '\t' -o '\t' 1,3,5-7) <(printf 'a\tb\tc\td\te\tf\tg\n' | cut -f 1,3,5-7)
+diff -w <(echo 'a b c d e' | cargo run -- --complement 2 -1) <(echo 'a b d')
+diff -w <(echo 'ssh -v --host example.com -p 22' | cargo run -- @/--host/+1) <(echo 'example.com')
+diff -w <(printf 'GET /a 200\nPOST /b 302\nGET /c 404\n' | cargo run -- --where '0==GET && -1!=200' 1) <(echo '/c')
+diff -w <(echo 'a b c d e f g' | cargo run -- --unique-fields --unique-order ascending 4 1:5) <(echo 'b c d e f')
+diff -w <(echo 'root example.com ssh 22' | cargo run -- --format '{0}@{1}:{-1:0>4}') <(echo 'root@example.com:0022')
+diff -w <(echo 'commit 3f2a9c81be04d7e1 2024-01-15' | cargo run -- '1[0:6]' '-1[-5:]') <(echo '3f2a9c8 01-15')
+diff -w <(printf 'a b c d e f g\na b c\n' | cargo run -- --fill - 0 5 6) <(printf 'a f g\na - -\n')
+diff -w <(echo 'a:b:c' | cargo run -- -d -f : 1 2>/dev/null) <(echo 'b')
+diff <(echo 'a:b' | cargo run -- -d -f : 1 2>&1 >/dev/null | grep '^  choice') <(echo '  choice 1: start 1, end 1 -> field 1')
+diff -w <(printf 'a 1\nb 2 x\n' | cargo run -- --describe | grep 'fields:') <(printf '2 fields: 1 line\n3 fields: 1 line\nfields:\n')
+diff -w <(printf 'a b c\nd\n' | cargo run -- --stats 2 2>/dev/null) <(printf 'c\n\n')
+diff <(printf 'a b c\nd\n' | cargo run -- --stats 2 2>&1 >/dev/null | grep -E '^(lines|fields|bytes) ') <(printf 'lines read: 2\nlines emitted: 2\nlines with an empty choice: 1\nfields per line: min 1, max 3, average 2.00\nbytes in: 8\nbytes out: 3\n')
+diff -w <(echo 'root:x:0:0::/root:/bin/bash' | cargo run -- --preset passwd user shell 2>/dev/null) <(echo 'root:/bin/bash')
+diff -w <(printf 'root:x:0:0::/root:/bin/bash\nbin:x:1:1::/:/sbin/nologin\n' | cargo run -- --preset passwd --where 'shell=/bin/bash' user 2>/dev/null) <(echo 'root')
+diff -w <(echo 'a,"b,c",d' | cargo run -- --preset csv 2 2>/dev/null) <(echo '"b,c"')
+diff -w <(echo 'a;"b;c"' | cargo run -- -f ';' --quote '"' 1 2>/dev/null) <(echo '"b;c"')
+diff -w <(echo '10.0.0.1 - - [10/Oct/2000:13:55:36 -0700] "GET / HTTP/1.1" 404 0 "-" "curl/8.0"' | cargo run -- --preset nginx --format '{status} {request}' 2>/dev/null) <(echo '404 GET / HTTP/1.1')
+diff <(cargo run -- --generate man 2>/dev/null | grep '^\.SH') <(printf '.SH NAME\n.SH SYNOPSIS\n.SH FLAGS\n.SH OPTIONS\n.SH ARGS\n.SH EXAMPLES\n')
+cargo run -- --generate bash 2>/dev/null | bash -n
+diff <(printf 'a b c\nd e\n' | cargo run -- --warn-unused 0 7 2>&1 >/dev/null | grep '^warning') <(echo 'warning: choice 7 never produced a field, from 2 lines with at most 3 fields')
+printf '#!/usr/bin/env -S choose --spec-file\n# users\nfield-separator :\noutput-field-separator ,\nchoices 0 -1\n' > "${config_home}/users.choose"
+diff -w <(echo 'root:x:0:0::/root:/bin/bash' | cargo run -- --spec-file "${config_home}/users.choose" 2>/dev/null) <(echo 'root,/bin/bash')
+diff -w <(echo 'root:x:0:0::/root:/bin/bash' | cargo run -- --spec-file "${config_home}/users.choose" -o ' ' 2 2>/dev/null) <(echo 'root /bin/bash 0')
+mkdir -p "${config_home}/choose"
+printf 'one-indexed = true\noutput-field-separator = ","\n' > "${config_home}/choose/config.toml"
+diff -w <(echo 'a b c' | cargo run -- 1 3 2>/dev/null) <(echo 'a,c')
+diff -w <(echo 'a b c' | CHOOSE_OPTS="-o ':'" cargo run -- 1 3 2>/dev/null) <(echo 'a:c')
+diff -w <(echo 'a b c' | cargo run -- --cut-syntax 1-2 2>/dev/null) <(echo 'a,b')
+diff -w <(echo 'a b c' | cargo run -- --no-config 1 3 2>/dev/null) <(echo 'b')
+printf '[presets.kv]\nfield-separator = "="\nfields = { key = 0, value = 1 }\n' > "${config_home}/choose/config.toml"
+diff -w <(echo 'a=1' | cargo run -- --preset kv value 2>/dev/null) <(echo '1')
+rm "${config_home}/choose/config.toml"
 # test CRLF for windows
 diff -w <(cargo run -- -f ';' -1 -i ${test_dir}/crlf.txt 2>/dev/null) <(cat "${test_dir}/crlf_output.txt")
 # add tests using piping
@@ -49,6 +93,27 @@ if [ $r -ne 2 ]; then
   exit 1
 fi
 
+printf 'a b c\na b\n' | cargo run -- --strict 2 >&/dev/null
+r=$?
+if [ $r -ne 4 ]; then
+  echo "Failed to return 4 on unsatisfied choice in strict mode"
+  exit 1
+fi
+
+cargo run -- 1 --escape '\' >&/dev/null </dev/null
+r=$?
+if [ $r -ne 2 ]; then
+  echo "Failed to return 2 on invalid configuration"
+  exit 1
+fi
+
+printf 'a\n\xff\n' | cargo run -- 0 >&/dev/null
+r=$?
+if [ $r -ne 5 ]; then
+  echo "Failed to return 5 on input read error"
+  exit 1
+fi
+
 file=/tmp/000_file
 touch $file
 chmod 000 $file
@@ -60,6 +125,8 @@ if [ $r -ne 3 ]; then
 fi
 rm -f $file
 
+rm -rf "$config_home"
+
 cd $orig_dir
 
 printf "\033[1;32mAll tests passed\033[0m\n"